@@ -0,0 +1,14 @@
+#import bevy_ecs_tilemap::common::process_fragment
+#import bevy_ecs_tilemap::vertex_output::MeshVertexOutput
+
+struct TintMaterial {
+    color: vec4<f32>,
+};
+
+@group(3) @binding(0)
+var<uniform> material: TintMaterial;
+
+@fragment
+fn fragment(in: MeshVertexOutput) -> @location(0) vec4<f32> {
+    return process_fragment(in) * material.color;
+}
@@ -0,0 +1,35 @@
+<?xml version="1.0" encoding="UTF-8"?>
+<tileset version="1.10" tiledversion="1.10.2" name="terrain" class="Ground" tilewidth="16" tileheight="16" tilecount="4" columns="4">
+ <tileoffset x="2" y="-4"/>
+ <image source="../textures/water.png" width="64" height="16"/>
+ <properties>
+  <property name="biome" value="coast"/>
+ </properties>
+ <tile id="1" class="Rock" probability="0.5">
+  <properties>
+   <property name="Health" type="int" value="4"/>
+  </properties>
+  <objectgroup draworder="index" id="2">
+   <object id="1" x="2" y="3" width="12" height="10"/>
+  </objectgroup>
+  <animation>
+   <frame tileid="1" duration="100"/>
+   <frame tileid="2" duration="200"/>
+  </animation>
+ </tile>
+ <wangsets>
+  <wangset name="Shore" type="corner" tile="-1">
+   <properties>
+    <property name="depth" type="float" value="1.5"/>
+   </properties>
+   <wangcolor name="Sand" color="#ffcc00" tile="0" probability="1"/>
+   <wangcolor name="Water" color="#0066ff" tile="3" probability="0.5">
+    <properties>
+     <property name="swimmable" type="bool" value="true"/>
+    </properties>
+   </wangcolor>
+   <wangtile tileid="0" wangid="0,1,0,1,0,1,0,1"/>
+   <wangtile tileid="3" wangid="0,2,0,2,0,1,0,1"/>
+  </wangset>
+ </wangsets>
+</tileset>
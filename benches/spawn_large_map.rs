@@ -0,0 +1,113 @@
+//! Time it takes to spawn a map of 256 by 256 tiles, in the frame spawning it.
+//!
+//! Run with `cargo bench --bench spawn_large_map`, adding `--features names` to include the
+//! names of tiles. The map is loaded in a headless app, so textures are not loaded and the time
+//! is spent creating the tile entities and their tilemap.
+//!
+//! The same number of tiles is then spawned one by one with their names, like before tiles were
+//! spawned in batches, and in a single batch without names, to compare both.
+
+use std::fmt::Write;
+
+use bevy::ecs::world::CommandQueue;
+use bevy::prelude::*;
+use bevy::utils::{Duration, Instant};
+use bevy_tiled_blueprints::headless::spawn_test_app;
+use bevy_tiled_blueprints::is_map_spawned;
+use bevy_tiled_blueprints::prelude::bevy_ecs_tilemap::prelude::*;
+use bevy_tiled_blueprints::prelude::*;
+
+const MAP_SIZE: u32 = 256;
+const RUNS: usize = 5;
+
+/// A single tile layer covering the map, cycling through the tiles of a tileset.
+fn large_map() -> String {
+    let mut map = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <map version=\"1.10\" orientation=\"orthogonal\" renderorder=\"right-down\" \
+         width=\"{MAP_SIZE}\" height=\"{MAP_SIZE}\" tilewidth=\"16\" tileheight=\"16\" \
+         infinite=\"0\">\n\
+         <tileset firstgid=\"1\" name=\"water\" tilewidth=\"16\" tileheight=\"16\" \
+         tilecount=\"4\" columns=\"4\">\n\
+         <image source=\"water.png\" width=\"64\" height=\"16\"/>\n\
+         </tileset>\n\
+         <layer id=\"1\" name=\"ground\" width=\"{MAP_SIZE}\" height=\"{MAP_SIZE}\">\n\
+         <data encoding=\"csv\">\n"
+    );
+    let tiles: Vec<String> = (0..MAP_SIZE * MAP_SIZE)
+        .map(|i| (i % 4 + 1).to_string())
+        .collect();
+    let _ = writeln!(map, "{}", tiles.join(","));
+    map.push_str("</data>\n</layer>\n</map>\n");
+    map
+}
+
+/// Spawns the tiles of a map in a new world, one by one with their names or in a single batch.
+fn spawn_tiles(batched: bool) -> Duration {
+    let mut world = World::new();
+    let tilemap_id = TilemapId(world.spawn_empty().id());
+    let start = Instant::now();
+    let mut queue = CommandQueue::default();
+    let mut commands = Commands::new(&mut queue, &world);
+    let tiles = (0..MAP_SIZE).flat_map(|x| (0..MAP_SIZE).map(move |y| TilePos { x, y }));
+    if batched {
+        let bundles: Vec<_> = tiles
+            .map(|position| TileBundle {
+                position,
+                tilemap_id,
+                ..Default::default()
+            })
+            .collect();
+        commands.spawn_batch(bundles);
+    } else {
+        for position in tiles {
+            commands.spawn((
+                TileBundle {
+                    position,
+                    tilemap_id,
+                    ..Default::default()
+                },
+                Name::new(format!("tile-0-{}x{}", position.x, position.y)),
+            ));
+        }
+    }
+    queue.apply(&mut world);
+    start.elapsed()
+}
+
+fn main() {
+    let dir = std::env::temp_dir().join("bevy_tiled_blueprints_bench");
+    std::fs::create_dir_all(&dir).expect("the temporary directory should be writable");
+    std::fs::write(dir.join("large.tmx"), large_map()).expect("the map should be written");
+
+    for run in 1..=RUNS {
+        let mut app = spawn_test_app(&dir);
+        let handle: Handle<TiledMap> = app.world().resource::<AssetServer>().load("large.tmx");
+        let map = app.world_mut().spawn(TiledMapBundle::new(handle)).id();
+        // The map is loaded on other threads, so the slowest frame is the one spawning it.
+        let start = Instant::now();
+        let mut slowest_frame = Duration::ZERO;
+        while !is_map_spawned(app.world(), map) {
+            assert!(
+                start.elapsed() < Duration::from_secs(60),
+                "the map was not spawned"
+            );
+            let frame_start = Instant::now();
+            app.update();
+            slowest_frame = slowest_frame.max(frame_start.elapsed());
+        }
+        let tiles = app
+            .world_mut()
+            .query::<&TileSource>()
+            .iter(app.world())
+            .count();
+        println!("run {run}: spawned {tiles} tiles, slowest frame {slowest_frame:?}");
+    }
+    for run in 1..=RUNS {
+        println!(
+            "run {run}: tiles spawned one by one in {:?}, in a batch in {:?}",
+            spawn_tiles(false),
+            spawn_tiles(true)
+        );
+    }
+}
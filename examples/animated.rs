@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+use bevy_tiled_blueprints::prelude::TiledMapCamera;
+
+fn startup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((Camera2dBundle::default(), TiledMapCamera));
+
+    let map_handle: Handle<bevy_tiled_blueprints::TiledMap> = asset_server.load("animated.tmx");
+
+    commands.spawn(bevy_tiled_blueprints::TiledMapBundle {
+        tiled_map: map_handle,
+        ..Default::default()
+    });
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(bevy_tiled_blueprints::prelude::bevy_ecs_tilemap::TilemapPlugin)
+        .add_plugins(bevy_tiled_blueprints::prelude::TiledBlueprintsPlugin {
+            fit_camera: true,
+            ..Default::default()
+        })
+        .add_systems(Startup, startup)
+        .run();
+}
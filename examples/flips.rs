@@ -0,0 +1,25 @@
+use bevy::prelude::*;
+
+fn startup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scale = 0.25;
+    commands.spawn(camera);
+
+    // Tiles with all eight combinations of the horizontal (H), vertical (V) and anti-diagonal (D)
+    // flips, from left to right: none, H, V, HV, D, DH, DV, DHV.
+    let map_handle: Handle<bevy_tiled_blueprints::TiledMap> = asset_server.load("flips.tmx");
+
+    commands.spawn(bevy_tiled_blueprints::TiledMapBundle {
+        tiled_map: map_handle,
+        ..Default::default()
+    });
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(bevy_tiled_blueprints::prelude::bevy_ecs_tilemap::TilemapPlugin)
+        .add_plugins(bevy_tiled_blueprints::prelude::TiledBlueprintsPlugin::default())
+        .add_systems(Startup, startup)
+        .run();
+}
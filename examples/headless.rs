@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+use bevy::utils::Duration;
+use bevy_tiled_blueprints::prelude::*;
+
+fn main() {
+    let mut app = bevy_tiled_blueprints::headless::spawn_test_app("assets");
+    let map_handle: Handle<TiledMap> = app.world().resource::<AssetServer>().load("isometric.tmx");
+    let map = app.world_mut().spawn(TiledMapBundle::new(map_handle)).id();
+
+    if !bevy_tiled_blueprints::headless::update_until_spawned(&mut app, map, Duration::from_secs(5))
+    {
+        eprintln!("The map was not spawned");
+        std::process::exit(1);
+    }
+
+    let world = app.world_mut();
+    let layers = world.get::<TiledLayersStorage>(map).unwrap().storage.len();
+    let tiles = world
+        .query::<&bevy_ecs_tilemap::tiles::TilePos>()
+        .iter(world)
+        .count();
+    let objects = world.query::<&TiledObject>().iter(world).count();
+    println!("Spawned {layers} layers with {tiles} tiles and {objects} objects");
+}
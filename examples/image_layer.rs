@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+
+fn startup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2dBundle::default());
+
+    let map_handle: Handle<bevy_tiled_blueprints::TiledMap> = asset_server.load("image_layer.tmx");
+
+    commands.spawn(bevy_tiled_blueprints::TiledMapBundle {
+        tiled_map: map_handle,
+        ..Default::default()
+    });
+}
+
+/// Moves the camera to show the parallax of the background.
+fn move_camera(time: Res<Time>, mut camera: Query<&mut Transform, With<Camera>>) {
+    for mut transform in camera.iter_mut() {
+        transform.translation.x = (time.elapsed_seconds() * 0.5).sin() * 80.0;
+    }
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(bevy_tiled_blueprints::prelude::bevy_ecs_tilemap::TilemapPlugin)
+        .add_plugins(bevy_tiled_blueprints::prelude::TiledBlueprintsPlugin {
+            apply_parallax: true,
+            ..Default::default()
+        })
+        .add_systems(Startup, startup)
+        .add_systems(Update, move_camera)
+        .run();
+}
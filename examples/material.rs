@@ -0,0 +1,54 @@
+use bevy::{prelude::*, render::render_resource::AsBindGroup};
+use bevy_tiled_blueprints::prelude::{
+    bevy_ecs_tilemap::prelude::{MaterialTilemap, MaterialTilemapPlugin},
+    RegisterTiledMaterial, TiledMapCamera,
+};
+
+/// Multiplies the colors of the tiles, used by layers with a `material` property set to
+/// `tinted`.
+#[derive(AsBindGroup, TypePath, Debug, Clone, Default, Asset)]
+pub struct TintMaterial {
+    #[uniform(0)]
+    color: Vec4,
+}
+
+impl MaterialTilemap for TintMaterial {
+    fn fragment_shader() -> bevy::render::render_resource::ShaderRef {
+        "shaders/tint.wgsl".into()
+    }
+}
+
+fn startup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((Camera2dBundle::default(), TiledMapCamera));
+
+    let map_handle: Handle<bevy_tiled_blueprints::TiledMap> = asset_server.load("material.tmx");
+
+    commands.spawn(bevy_tiled_blueprints::TiledMapBundle {
+        tiled_map: map_handle,
+        ..Default::default()
+    });
+}
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(bevy_tiled_blueprints::prelude::bevy_ecs_tilemap::TilemapPlugin)
+        .add_plugins(MaterialTilemapPlugin::<TintMaterial>::default())
+        .add_plugins(bevy_tiled_blueprints::prelude::TiledBlueprintsPlugin {
+            fit_camera: true,
+            ..Default::default()
+        })
+        .add_systems(Startup, startup);
+
+    let tint = app
+        .world_mut()
+        .resource_mut::<Assets<TintMaterial>>()
+        .add(TintMaterial {
+            color: Vec4::new(1.0, 0.4, 0.4, 1.0),
+        });
+    app.register_tiled_material("tinted", move |entity| {
+        entity.insert(tint.clone());
+    });
+
+    app.run();
+}
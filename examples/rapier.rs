@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use bevy_tiled_blueprints::prelude::{TiledMapCamera, TiledMapSettings};
+
+fn startup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((Camera2dBundle::default(), TiledMapCamera));
+
+    // Colliders of tiles are only generated when enabled in the settings.
+    let map_handle: Handle<bevy_tiled_blueprints::TiledMap> = asset_server
+        .load_with_settings("rapier.tmx", |settings: &mut TiledMapSettings| {
+            settings.generate_colliders = true;
+        });
+
+    commands.spawn(bevy_tiled_blueprints::TiledMapBundle {
+        tiled_map: map_handle,
+        ..Default::default()
+    });
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugins(bevy_tiled_blueprints::prelude::bevy_ecs_tilemap::TilemapPlugin)
+        .add_plugins(bevy_tiled_blueprints::prelude::TiledBlueprintsPlugin {
+            fit_camera: true,
+            ..Default::default()
+        })
+        // The map is measured in pixels, 16 of them being a tile.
+        .add_plugins((
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(16.0),
+            RapierDebugRenderPlugin::default(),
+        ))
+        .add_systems(Startup, startup)
+        .run();
+}
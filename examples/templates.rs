@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bevy_tiled_blueprints::prelude::{
+    objects_with_class, TiledMapCamera, TiledMapSpawned, TiledMaps, TiledObject, TiledSystems,
+};
+
+fn startup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((Camera2dBundle::default(), TiledMapCamera));
+
+    let map_handle: Handle<bevy_tiled_blueprints::TiledMap> = asset_server.load("templates.tmx");
+
+    commands.spawn(bevy_tiled_blueprints::TiledMapBundle {
+        tiled_map: map_handle,
+        ..Default::default()
+    });
+}
+
+/// Set in the templates, instances can override it.
+#[derive(Debug, Reflect, Component, Default, Clone)]
+#[reflect(Component)]
+pub struct Health(pub i32);
+
+#[derive(Debug, Reflect, Component, Default, Clone)]
+#[reflect(Component)]
+pub struct Pickup;
+
+fn print_objects(
+    mut events: EventReader<TiledMapSpawned>,
+    objects: Query<(&Name, &TiledObject, Option<&Health>, Has<Pickup>)>,
+) {
+    for _ in events.read() {
+        for (name, object, health, pickup) in objects.iter() {
+            info!(
+                "{name} of class {:?}: {health:?}, pickup: {pickup}",
+                object.class
+            );
+        }
+    }
+}
+
+/// Spawn points have no component of their own, they are found by their class.
+fn print_spawn_points(
+    mut events: EventReader<TiledMapSpawned>,
+    objects: Query<(Entity, &TiledObject)>,
+    transforms: Query<&Transform>,
+) {
+    for _ in events.read() {
+        for spawn_point in objects_with_class(&objects, "SpawnPoint") {
+            if let Ok(transform) = transforms.get(spawn_point) {
+                info!("Spawn point at {} in its layer", transform.translation);
+            }
+        }
+    }
+}
+
+/// The loaded map is read from the map entity of the event.
+fn print_layers(mut events: EventReader<TiledMapSpawned>, maps: TiledMaps) {
+    for event in events.read() {
+        if let Some(map) = maps.get_map(event.map_entity) {
+            let layers: Vec<_> = map.layer_names().collect();
+            info!("Spawned the layers {layers:?}");
+        }
+    }
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .register_type::<Health>()
+        .register_type::<Pickup>()
+        .add_plugins(bevy_tiled_blueprints::prelude::bevy_ecs_tilemap::TilemapPlugin)
+        .add_plugins(bevy_tiled_blueprints::prelude::TiledBlueprintsPlugin {
+            fit_camera: true,
+            ..Default::default()
+        })
+        .add_systems(Startup, startup)
+        .add_systems(
+            Update,
+            (print_objects, print_spawn_points, print_layers).after(TiledSystems::Process),
+        )
+        .run();
+}
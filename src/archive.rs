@@ -0,0 +1,187 @@
+//! Archives bundling the files referenced by maps, read by
+//! [`TiledLoader::with_archive`](crate::TiledLoader::with_archive).
+//!
+//! Only uncompressed tar archives are supported, in the `ustar` format written by most tools,
+//! like `tar -cf maps.tar tilesets textures`.
+
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use bevy::utils::HashMap;
+
+const BLOCK_SIZE: usize = 512;
+
+/// Files of an archive, by their normalized path.
+#[derive(Debug, Default)]
+pub(crate) struct Archive {
+    files: HashMap<PathBuf, Arc<[u8]>>,
+}
+
+impl Archive {
+    pub(crate) fn from_tar(bytes: &[u8]) -> Result<Self, Error> {
+        let mut files = HashMap::default();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let header = bytes
+                .get(offset..offset + BLOCK_SIZE)
+                .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "truncated tar archive"))?;
+            // The archive ends with empty blocks.
+            if header.iter().all(|b| *b == 0) {
+                break;
+            }
+            let size = parse_octal(&header[124..136])?;
+            let start = offset + BLOCK_SIZE;
+            let data = bytes
+                .get(start..start + size)
+                .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "truncated tar archive"))?;
+            // Other entries, like directories and links, are skipped.
+            if matches!(header[156], b'0' | 0) {
+                let name = field_str(&header[0..100])?;
+                let prefix = field_str(&header[345..500])?;
+                let path = Path::new(prefix).join(name);
+                files.insert(crate::normalize_path(&path), Arc::from(data));
+            }
+            offset = start + size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+        }
+        Ok(Self { files })
+    }
+
+    pub(crate) fn get(&self, path: &Path) -> Option<Arc<[u8]>> {
+        self.files.get(&crate::normalize_path(path)).cloned()
+    }
+}
+
+/// Reads a text field of a header, which ends at the first null byte.
+fn field_str(field: &[u8]) -> Result<&str, Error> {
+    let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    std::str::from_utf8(&field[..end]).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
+
+fn parse_octal(field: &[u8]) -> Result<usize, Error> {
+    let digits = field_str(field)?.trim_matches(|c: char| c == ' ' || c == '\0');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    usize::from_str_radix(digits, 8).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Writes an entry of a `ustar` archive, its header and its data padded to whole blocks.
+    pub(crate) fn tar_entry(path: &str, kind: u8, data: &[u8]) -> Vec<u8> {
+        // Long paths are split at a separator, like tar does.
+        let (prefix, name) = match path.len() > 100 {
+            true => path.rsplit_once('/').unwrap(),
+            false => ("", path),
+        };
+        let mut header = [0; BLOCK_SIZE];
+        header[0..name.len()].copy_from_slice(name.as_bytes());
+        header[100..107].copy_from_slice(b"0000644");
+        header[124..135].copy_from_slice(format!("{:011o}", data.len()).as_bytes());
+        header[156] = kind;
+        header[257..263].copy_from_slice(b"ustar\0");
+        header[263..265].copy_from_slice(b"00");
+        header[345..345 + prefix.len()].copy_from_slice(prefix.as_bytes());
+        let mut entry = header.to_vec();
+        entry.extend_from_slice(data);
+        entry.resize(entry.len().div_ceil(BLOCK_SIZE) * BLOCK_SIZE, 0);
+        entry
+    }
+
+    /// Ends an archive with its two empty blocks.
+    pub(crate) fn tar(entries: impl IntoIterator<Item = Vec<u8>>) -> Vec<u8> {
+        let mut bytes: Vec<u8> = entries.into_iter().flatten().collect();
+        bytes.resize(bytes.len() + 2 * BLOCK_SIZE, 0);
+        bytes
+    }
+
+    #[test]
+    fn files_are_found_by_their_normalized_path() {
+        let archive = Archive::from_tar(&tar([
+            tar_entry("tilesets/./ground.tsx", b'0', b"<tileset/>"),
+            tar_entry("textures/ground.png", 0, b"png"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            archive.get(Path::new("tilesets/ground.tsx")).as_deref(),
+            Some(&b"<tileset/>"[..])
+        );
+        // Entries of old archives have no type.
+        assert_eq!(
+            archive
+                .get(Path::new("tilesets/../textures/ground.png"))
+                .as_deref(),
+            Some(&b"png"[..])
+        );
+        assert!(archive.get(Path::new("ground.png")).is_none());
+    }
+
+    #[test]
+    fn long_paths_are_joined_with_their_prefix() {
+        let dir = "a".repeat(120);
+        let path = format!("{dir}/ground.tsx");
+        let entry = tar_entry(&path, b'0', b"<tileset/>");
+        assert_eq!(&entry[0..11], b"ground.tsx\0");
+        let archive = Archive::from_tar(&tar([entry])).unwrap();
+        assert!(archive.get(Path::new(&path)).is_some());
+    }
+
+    #[test]
+    fn data_is_padded_to_whole_blocks() {
+        let sizes = [0, 1, BLOCK_SIZE - 1, BLOCK_SIZE, BLOCK_SIZE + 1];
+        let data: Vec<Vec<u8>> = sizes.iter().map(|size| vec![7; *size]).collect();
+        let archive = Archive::from_tar(&tar(data
+            .iter()
+            .enumerate()
+            .map(|(i, data)| tar_entry(&format!("{i}.bin"), b'0', data))))
+        .unwrap();
+        for (i, data) in data.iter().enumerate() {
+            assert_eq!(
+                archive.get(Path::new(&format!("{i}.bin"))).as_deref(),
+                Some(&data[..])
+            );
+        }
+    }
+
+    #[test]
+    fn directories_and_links_are_skipped() {
+        let archive = Archive::from_tar(&tar([
+            tar_entry("tilesets", b'5', &[]),
+            tar_entry("ground.tsx", b'2', &[]),
+            tar_entry("tilesets/ground.tsx", b'0', b"<tileset/>"),
+        ]))
+        .unwrap();
+        assert!(archive.get(Path::new("tilesets")).is_none());
+        assert!(archive.get(Path::new("ground.tsx")).is_none());
+        assert!(archive.get(Path::new("tilesets/ground.tsx")).is_some());
+    }
+
+    #[test]
+    fn archives_without_end_blocks_are_read() {
+        let entry = tar_entry("ground.tsx", b'0', b"<tileset/>");
+        let archive = Archive::from_tar(&entry).unwrap();
+        assert!(archive.get(Path::new("ground.tsx")).is_some());
+        assert!(Archive::from_tar(&[]).unwrap().files.is_empty());
+    }
+
+    #[test]
+    fn truncated_archives_are_rejected() {
+        let entry = tar_entry("ground.tsx", b'0', &[7; 600]);
+        // Cut in the data of the entry, and in a header.
+        for len in [BLOCK_SIZE + 100, BLOCK_SIZE - 1] {
+            let err = Archive::from_tar(&entry[..len]).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::UnexpectedEof, "{len}");
+        }
+    }
+
+    #[test]
+    fn invalid_sizes_are_rejected() {
+        let mut entry = tar_entry("ground.tsx", b'0', b"<tileset/>");
+        entry[124..135].copy_from_slice(b"0000000009x");
+        let err = Archive::from_tar(&tar([entry])).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,129 @@
+//! Colliders of [Avian](https://github.com/Jondolf/avian), enabled with the `avian` feature.
+//!
+//! Objects get a [`Collider`] matching their [`TiledObjectShape`], polygons are replaced by their
+//! convex hull. Objects do not get a rigid body, it can be added with a `RigidBody` string
+//! property set to `Static`, `Dynamic` or `Kinematic`.
+//!
+//! Tiles with [`TileColliders`], generated with
+//! [`TiledMapSettings::generate_colliders`](crate::TiledMapSettings::generate_colliders), get a
+//! static rigid body with all of their shapes, as a compound collider or as child entities with
+//! [`TileColliderChildren`]. `PhysicsPlugins` have to be added to the app.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::{
+    shapes::{TileColliderChildren, TileColliders, TiledObjectShape},
+    TiledSystems,
+};
+
+pub(crate) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (insert_object_colliders, insert_tile_colliders).after(TiledSystems::Process),
+    );
+}
+
+/// Collider of a shape and its position relative to the position of the shape.
+///
+/// Rectangles and ellipses are placed at their center, like in the debug display.
+fn shape_collider(shape: &TiledObjectShape) -> Option<(Vec2, Collider)> {
+    match shape {
+        TiledObjectShape::Rect {
+            width,
+            height,
+            center,
+        } if *width > 0.0 && *height > 0.0 => Some((*center, Collider::rectangle(*width, *height))),
+        TiledObjectShape::Ellipse {
+            width,
+            height,
+            center,
+        } if *width > 0.0 && *height > 0.0 => {
+            let collider = if width == height {
+                Collider::circle(width / 2.0)
+            } else {
+                Collider::ellipse(width / 2.0, height / 2.0)
+            };
+            Some((*center, collider))
+        }
+        TiledObjectShape::Polygon(points) => {
+            Some((Vec2::ZERO, Collider::convex_hull(points.clone())?))
+        }
+        TiledObjectShape::Polyline(points) if points.len() > 1 => {
+            Some((Vec2::ZERO, Collider::polyline(points.clone(), None)))
+        }
+        _ => None,
+    }
+}
+
+/// Collider placed at the position of its shape, from the result of [`shape_collider`].
+fn positioned_collider((offset, collider): (Vec2, Collider)) -> Collider {
+    if offset == Vec2::ZERO {
+        collider
+    } else {
+        Collider::compound(vec![(offset, Rotation::default(), collider)])
+    }
+}
+
+fn insert_object_colliders(
+    mut commands: Commands,
+    objects: Query<(Entity, &TiledObjectShape), Added<TiledObjectShape>>,
+) {
+    for (entity, shape) in objects.iter() {
+        if let Some(collider) = shape_collider(shape) {
+            commands
+                .entity(entity)
+                .insert(positioned_collider(collider));
+        }
+    }
+}
+
+/// Tiles are not positioned with a [`Transform`] by `bevy_ecs_tilemap`, so it is inserted from
+/// their [`TilePos`].
+fn insert_tile_colliders(
+    mut commands: Commands,
+    tiles: Query<(Entity, &TilePos, &TilemapId, &TileColliders), Added<TileColliders>>,
+    tilemaps: Query<(&TilemapGridSize, &TilemapType)>,
+    collider_children: Query<(), With<TileColliderChildren>>,
+) {
+    for (entity, tile_pos, tilemap_id, tile_colliders) in tiles.iter() {
+        let Ok((grid_size, map_type)) = tilemaps.get(tilemap_id.0) else {
+            continue;
+        };
+        let colliders: Vec<_> = tile_colliders
+            .0
+            .iter()
+            .filter_map(|(position, shape)| Some((*position, shape_collider(shape)?)))
+            .collect();
+        if colliders.is_empty() {
+            continue;
+        }
+        let center = tile_pos.center_in_world(grid_size, map_type);
+        let mut tile = commands.entity(entity);
+        tile.insert((
+            RigidBody::Static,
+            TransformBundle::from_transform(Transform::from_translation(center.extend(0.0))),
+        ));
+        if collider_children.contains(entity) {
+            tile.with_children(|parent| {
+                for (position, collider) in colliders {
+                    parent.spawn((
+                        positioned_collider(collider),
+                        TransformBundle::from_transform(Transform::from_translation(
+                            position.extend(0.0),
+                        )),
+                    ));
+                }
+            });
+        } else {
+            let shapes = colliders
+                .into_iter()
+                .map(|(position, (offset, collider))| {
+                    (position + offset, Rotation::default(), collider)
+                })
+                .collect();
+            tile.insert(Collider::compound(shapes));
+        }
+    }
+}
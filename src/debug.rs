@@ -1,6 +1,7 @@
 use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
 
-use crate::MapObject;
+use crate::{is_mirrored, shapes::TiledObjectShape, MapObject, TileLayerMarker};
 
 pub const MY_ACCENT_COLOR: Color = Color::LinearRgba(LinearRgba {
     red: 0.901,
@@ -9,17 +10,254 @@ pub const MY_ACCENT_COLOR: Color = Color::LinearRgba(LinearRgba {
     alpha: 1.0,
 });
 
+/// Settings of the debug rendering.
+#[derive(Resource, Reflect, Debug, Clone)]
+#[reflect(Resource)]
+pub struct TiledDebugConfig {
+    /// Draws the debug shapes, can be toggled at runtime.
+    pub enabled: bool,
+    /// Color of the drawn object shapes.
+    pub color: Color,
+    /// Half of the size of the cross drawn for point objects.
+    pub point_radius: f32,
+    /// Draws the outlines of the cells of tile layers, following their grid.
+    pub draw_grid: bool,
+    /// Color of the grid lines.
+    pub grid_color: Color,
+    /// Shows the names of objects above them, with texts spawned as children of the objects.
+    pub show_labels: bool,
+    /// Font size of the names of objects.
+    pub label_size: f32,
+}
+
+impl Default for TiledDebugConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            color: MY_ACCENT_COLOR,
+            point_radius: 10.,
+            draw_grid: false,
+            grid_color: Color::srgba(1., 1., 1., 0.25),
+            show_labels: false,
+            label_size: 12.,
+        }
+    }
+}
+
 pub struct TiledBlueprintsDebugDisplayPlugin;
 
 impl Plugin for TiledBlueprintsDebugDisplayPlugin {
     fn build(&self, app: &mut bevy::app::App) {
-        app.add_systems(Update, draw_objects);
+        app.init_resource::<TiledDebugConfig>()
+            .register_type::<TiledDebugConfig>()
+            .add_systems(
+                Update,
+                (draw_objects, draw_grid.run_if(grid_enabled)).run_if(debug_enabled),
+            )
+            .add_systems(Update, (update_labels, orient_labels).chain());
+    }
+}
+
+fn debug_enabled(config: Res<TiledDebugConfig>) -> bool {
+    config.enabled
+}
+
+fn grid_enabled(config: Res<TiledDebugConfig>) -> bool {
+    config.draw_grid
+}
+
+/// Text showing the name of an object, spawned as its child.
+#[derive(Component)]
+struct DebugLabel;
+
+/// Marks objects whose [`DebugLabel`] was spawned.
+#[derive(Component)]
+struct Labeled;
+
+/// Spawns the labels of new objects while they are shown, labels are respawned when the config
+/// changes so they follow its color and font size.
+fn update_labels(
+    mut commands: Commands,
+    config: Res<TiledDebugConfig>,
+    objects: Query<(Entity, &Name, Has<Labeled>), With<MapObject>>,
+    labeled: Query<Entity, With<Labeled>>,
+    labels: Query<Entity, With<DebugLabel>>,
+) {
+    if config.is_changed() {
+        for label in labels.iter() {
+            commands.entity(label).despawn_recursive();
+        }
+        for object in labeled.iter() {
+            commands.entity(object).remove::<Labeled>();
+        }
+    }
+    if !(config.enabled && config.show_labels) {
+        return;
+    }
+    let style = TextStyle {
+        font_size: config.label_size,
+        color: config.color,
+        ..Default::default()
+    };
+    for (object, name, _) in objects.iter().filter(|(_, _, labeled)| !labeled) {
+        // Objects are positioned at their top-left corner in Tiled, the label is right above it.
+        commands
+            .spawn((
+                Name::new("DebugLabel"),
+                DebugLabel,
+                Text2dBundle {
+                    text: Text::from_section(name.as_str(), style.clone()),
+                    text_anchor: bevy::sprite::Anchor::BottomLeft,
+                    transform: Transform::from_xyz(0., 2., 1.),
+                    ..Default::default()
+                },
+            ))
+            .set_parent(object);
+        commands.entity(object).insert(Labeled);
+    }
+}
+
+/// Keeps the labels upright and above their object in the mirrored maps of
+/// [`YAxis::YDown`](crate::YAxis::YDown).
+fn orient_labels(
+    objects: Query<&GlobalTransform, With<Labeled>>,
+    mut labels: Query<(&Parent, &mut Transform), With<DebugLabel>>,
+) {
+    for (object, mut transform) in labels.iter_mut() {
+        let Ok(object_transform) = objects.get(object.get()) else {
+            continue;
+        };
+        let sign = if is_mirrored(object_transform) {
+            -1.
+        } else {
+            1.
+        };
+        if transform.scale.y != sign {
+            transform.translation.y = 2. * sign;
+            transform.scale.y = sign;
+        }
+    }
+}
+
+/// Corners of a cell around its center, hexagons fill the spacing of rows or columns of
+/// `bevy_ecs_tilemap`.
+fn cell_outline(grid_size: &TilemapGridSize, map_type: &TilemapType) -> Vec<Vec2> {
+    let (x, y) = (grid_size.x / 2., grid_size.y / 2.);
+    match map_type {
+        TilemapType::Square => vec![
+            Vec2::new(-x, -y),
+            Vec2::new(x, -y),
+            Vec2::new(x, y),
+            Vec2::new(-x, y),
+        ],
+        TilemapType::Isometric(_) => vec![
+            Vec2::new(0., -y),
+            Vec2::new(x, 0.),
+            Vec2::new(0., y),
+            Vec2::new(-x, 0.),
+        ],
+        TilemapType::Hexagon(
+            HexCoordSystem::Row | HexCoordSystem::RowEven | HexCoordSystem::RowOdd,
+        ) => vec![
+            Vec2::new(0., -y),
+            Vec2::new(x, -y / 2.),
+            Vec2::new(x, y / 2.),
+            Vec2::new(0., y),
+            Vec2::new(-x, y / 2.),
+            Vec2::new(-x, -y / 2.),
+        ],
+        TilemapType::Hexagon(_) => vec![
+            Vec2::new(-x, 0.),
+            Vec2::new(-x / 2., -y),
+            Vec2::new(x / 2., -y),
+            Vec2::new(x, 0.),
+            Vec2::new(x / 2., y),
+            Vec2::new(-x / 2., y),
+        ],
+    }
+}
+
+/// Draws every cell of the visible tile layers, so cells shared by layers are drawn once per
+/// layer.
+fn draw_grid(
+    mut gizmos: Gizmos,
+    config: Res<TiledDebugConfig>,
+    layers: Query<
+        (
+            &GlobalTransform,
+            &TilemapSize,
+            &TilemapGridSize,
+            &TilemapType,
+            &InheritedVisibility,
+        ),
+        With<TileLayerMarker>,
+    >,
+) {
+    for (t, size, grid_size, map_type, visibility) in layers.iter() {
+        if !visibility.get() {
+            continue;
+        }
+        let outline = cell_outline(grid_size, map_type);
+        for x in 0..size.x {
+            for y in 0..size.y {
+                let center = TilePos { x, y }.center_in_world(grid_size, map_type);
+                let corners = outline.iter().chain(outline.first());
+                gizmos.linestrip_2d(
+                    corners
+                        .map(|corner| t.transform_point((center + *corner).extend(0.)).truncate()),
+                    config.grid_color,
+                );
+            }
+        }
     }
 }
 
-fn draw_objects(mut gizmos: Gizmos, q: Query<&GlobalTransform, With<MapObject>>) {
-    for t in q.iter() {
-        let t = t.translation();
-        gizmos.circle_2d(Vec2::new(t.x, t.y), 10., MY_ACCENT_COLOR);
+/// Draws the shapes of objects through their [`GlobalTransform`], which includes the mirroring of
+/// [`YAxis::YDown`](crate::YAxis::YDown) maps. Shapes follow the Y axis of their map, so they
+/// land on their objects either way.
+fn draw_objects(
+    mut gizmos: Gizmos,
+    config: Res<TiledDebugConfig>,
+    q: Query<(&GlobalTransform, &TiledObjectShape), With<MapObject>>,
+) {
+    let color = config.color;
+    for (t, shape) in q.iter() {
+        let to_world = |point: Vec2| t.transform_point(point.extend(0.)).truncate();
+        let (_, rotation, _) = t.to_scale_rotation_translation();
+        let angle = rotation.to_euler(EulerRot::ZYX).0;
+        match shape {
+            TiledObjectShape::Rect {
+                width,
+                height,
+                center,
+            } => {
+                gizmos.rect_2d(to_world(*center), angle, Vec2::new(*width, *height), color);
+            }
+            TiledObjectShape::Ellipse {
+                width,
+                height,
+                center,
+            } => {
+                let center = to_world(*center);
+                let half_size = Vec2::new(width / 2., height / 2.);
+                gizmos.ellipse_2d(center, angle, half_size, color);
+            }
+            TiledObjectShape::Polygon(points) => {
+                let closed = points.iter().chain(points.first());
+                gizmos.linestrip_2d(closed.map(|p| to_world(*p)), color);
+            }
+            TiledObjectShape::Polyline(points) => {
+                gizmos.linestrip_2d(points.iter().map(|p| to_world(*p)), color);
+            }
+            TiledObjectShape::Point => {
+                let center = to_world(Vec2::ZERO);
+                let (a, b) = (
+                    Vec2::splat(config.point_radius),
+                    Vec2::new(config.point_radius, -config.point_radius),
+                );
+                gizmos.line_2d(center - a, center + a, color);
+                gizmos.line_2d(center - b, center + b, color);
+            }
+        }
     }
 }
@@ -0,0 +1,46 @@
+//! Diagnostics of spawning maps, enabled with the `diagnostics` feature.
+//!
+//! Measurements are added for every frame in which at least one map was spawned, so they
+//! show up in the [`LogDiagnosticsPlugin`](bevy::diagnostic::LogDiagnosticsPlugin) output and in
+//! the [`DiagnosticsStore`](bevy::diagnostic::DiagnosticsStore).
+
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::prelude::*;
+use bevy::utils::Duration;
+
+/// Number of tiles spawned.
+pub const TILES: DiagnosticPath = DiagnosticPath::const_new("tiled_blueprints/tiles");
+/// Number of objects spawned.
+pub const OBJECTS: DiagnosticPath = DiagnosticPath::const_new("tiled_blueprints/objects");
+/// Number of layers spawned.
+pub const LAYERS: DiagnosticPath = DiagnosticPath::const_new("tiled_blueprints/layers");
+/// Time spent spawning the maps, in milliseconds.
+pub const SPAWN_TIME: DiagnosticPath = DiagnosticPath::const_new("tiled_blueprints/spawn_time");
+
+pub(crate) fn register(app: &mut App) {
+    app.register_diagnostic(Diagnostic::new(TILES))
+        .register_diagnostic(Diagnostic::new(OBJECTS))
+        .register_diagnostic(Diagnostic::new(LAYERS))
+        .register_diagnostic(Diagnostic::new(SPAWN_TIME).with_suffix("ms"));
+}
+
+/// Entities spawned by a single run of [`process_loaded_maps`](crate::process_loaded_maps).
+#[derive(Default)]
+pub(crate) struct SpawnCounts {
+    pub maps: usize,
+    pub tiles: usize,
+    pub objects: usize,
+    pub layers: usize,
+}
+
+impl SpawnCounts {
+    pub(crate) fn measure(&self, diagnostics: &mut Diagnostics, elapsed: Duration) {
+        if self.maps == 0 {
+            return;
+        }
+        diagnostics.add_measurement(&TILES, || self.tiles as f64);
+        diagnostics.add_measurement(&OBJECTS, || self.objects as f64);
+        diagnostics.add_measurement(&LAYERS, || self.layers as f64);
+        diagnostics.add_measurement(&SPAWN_TIME, || elapsed.as_secs_f64() * 1000.0);
+    }
+}
@@ -0,0 +1,223 @@
+//! Images embedded in maps and tilesets, which the `tiled` crate cannot parse.
+//!
+//! Tiled can store the image of a tileset or an image layer as base64 data inside of its
+//! `<image>` element, and some tools write images as `data:` URIs in their `source`. The `tiled`
+//! crate only reads image files, so before a document is parsed, its embedded images are replaced
+//! with files named after it, like `map.tmx.0.png`, whose decoded bytes are loaded instead.
+//!
+//! Only PNG images are supported, like in archives.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use base64::Engine;
+use bevy::log;
+use bevy::utils::HashMap;
+
+/// Decoded images, by the normalized path of the files replacing them.
+pub(crate) type EmbeddedImages = HashMap<PathBuf, Arc<[u8]>>;
+
+/// Replaces the embedded images of a TMX or TSX document at `path` with image files next to it.
+///
+/// The decoded images are added to `images` by the normalized path of their file. Returns `None`
+/// when the document has no embedded images.
+pub(crate) fn extract_images(
+    document: &[u8],
+    path: &Path,
+    images: &mut EmbeddedImages,
+) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(document).ok()?;
+    let file_name = path.file_name()?.to_string_lossy();
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut count = 0;
+    while let Some(start) = rest.find("<image") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(tag_end) = rest.find('>') else {
+            break;
+        };
+        let (tag, after) = rest.split_at(tag_end + 1);
+        // Skips `<imagelayer>` elements, which contain the `<image>` of the layer.
+        let is_image = tag[6..].starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>');
+        let Some(image) = is_image.then(|| embedded_image(tag, after)).flatten() else {
+            out.push_str(tag);
+            rest = after;
+            continue;
+        };
+        let Ok(bytes) = image.data else {
+            log::warn!(
+                "Skipping an embedded image of {:?} with invalid data.",
+                path
+            );
+            out.push_str(tag);
+            rest = after;
+            continue;
+        };
+        let name = format!("{}.{}.{}", file_name, count, image.format);
+        count += 1;
+        images.insert(crate::normalize_path(&dir.join(&name)), Arc::from(bytes));
+        out.push_str(&format!("<image{} source=\"{}\"/>", image.attrs, name));
+        rest = &after[image.consumed..];
+    }
+    if count == 0 {
+        return None;
+    }
+    out.push_str(rest);
+    Some(out.into_bytes())
+}
+
+/// Image of an `<image>` tag, either from a `data:` URI or from a `<data>` child.
+struct EmbeddedImage<'a> {
+    data: Result<Vec<u8>, base64::DecodeError>,
+    /// Extension of the image file.
+    format: &'a str,
+    /// The other attributes of the tag.
+    attrs: String,
+    /// Length of the content of the element following the tag, dropped with it.
+    consumed: usize,
+}
+
+/// Returns `None` if the image of the tag is a file.
+fn embedded_image<'a>(tag: &'a str, after: &str) -> Option<EmbeddedImage<'a>> {
+    let self_closing = tag.ends_with("/>");
+    let attrs = tag[6..tag.len() - if self_closing { 2 } else { 1 }].trim_end();
+
+    if let Some(source) = attr(attrs, "source") {
+        let (media_type, data) = source.strip_prefix("data:")?.split_once(',')?;
+        let format = media_type
+            .strip_suffix(";base64")?
+            .strip_prefix("image/")
+            .unwrap_or("png");
+        // Drops the attribute along with the whitespace before it.
+        let source_attr = format!("source=\"{}\"", source);
+        let start = attrs.find(&source_attr)?;
+        let attrs = format!(
+            "{}{}",
+            attrs[..start].trim_end(),
+            &attrs[start + source_attr.len()..]
+        );
+        // The element may still have children, like a `<data>` one, which are dropped.
+        let consumed = match self_closing {
+            true => 0,
+            false => after.find("</image>")? + "</image>".len(),
+        };
+        return Some(EmbeddedImage {
+            data: decode(data),
+            format,
+            attrs: attrs.trim_end().to_string(),
+            consumed,
+        });
+    }
+
+    if self_closing {
+        return None;
+    }
+    let end = after.find("</image>")?;
+    let content = &after[..end];
+    let data_start = content.find("<data")?;
+    let data_tag_end = data_start + content[data_start..].find('>')?;
+    if attr(&content[data_start..data_tag_end], "encoding") != Some("base64") {
+        return None;
+    }
+    let data_end = content.find("</data>")?;
+    let data = &content[data_tag_end + 1..data_end];
+    Some(EmbeddedImage {
+        data: decode(data),
+        format: attr(attrs, "format").unwrap_or("png"),
+        attrs: attrs.to_string(),
+        consumed: end + "</image>".len(),
+    })
+}
+
+/// Value of an attribute in the text of a tag.
+pub(crate) fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{}=\"", name);
+    let start = tag
+        .match_indices(&pattern)
+        .find(|(index, _)| tag[..*index].ends_with(char::is_whitespace))?
+        .0
+        + pattern.len();
+    let len = tag[start..].find('"')?;
+    Some(&tag[start..start + len])
+}
+
+fn decode(data: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    let data: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+    base64::engine::general_purpose::STANDARD.decode(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_images_are_replaced_by_numbered_files() {
+        let document = r#"<map>
+ <tileset name="tiles">
+  <image format="png" width="1" height="1">
+   <data encoding="base64">
+    AQID
+   </data>
+  </image>
+ </tileset>
+ <imagelayer id="2" name="background">
+  <image source="data:image/jpeg;base64,BAUG" width="1" height="1"/>
+ </imagelayer>
+ <imagelayer id="3" name="foreground">
+  <image source="foreground.png" width="1" height="1"/>
+ </imagelayer>
+</map>"#;
+        let mut images = EmbeddedImages::default();
+        let out =
+            extract_images(document.as_bytes(), Path::new("maps/map.tmx"), &mut images).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(
+            out,
+            r#"<map>
+ <tileset name="tiles">
+  <image format="png" width="1" height="1" source="map.tmx.0.png"/>
+ </tileset>
+ <imagelayer id="2" name="background">
+  <image width="1" height="1" source="map.tmx.1.jpeg"/>
+ </imagelayer>
+ <imagelayer id="3" name="foreground">
+  <image source="foreground.png" width="1" height="1"/>
+ </imagelayer>
+</map>"#
+        );
+        assert_eq!(images.len(), 2);
+        assert_eq!(
+            images
+                .get(Path::new("maps/map.tmx.0.png"))
+                .map(|bytes| &bytes[..]),
+            Some(&[1, 2, 3][..])
+        );
+        assert_eq!(
+            images
+                .get(Path::new("maps/map.tmx.1.jpeg"))
+                .map(|bytes| &bytes[..]),
+            Some(&[4, 5, 6][..])
+        );
+    }
+
+    #[test]
+    fn documents_without_embedded_images_are_kept() {
+        let document = r#"<tileset name="tiles">
+ <image source="tiles.png" width="1" height="1"/>
+</tileset>"#;
+        let mut images = EmbeddedImages::default();
+        assert!(extract_images(document.as_bytes(), Path::new("tiles.tsx"), &mut images).is_none());
+        assert!(images.is_empty());
+    }
+
+    #[test]
+    fn images_with_invalid_data_are_kept() {
+        let document = r#"<image source="data:image/png;base64,!!!" width="1" height="1"/>"#;
+        let mut images = EmbeddedImages::default();
+        assert!(extract_images(document.as_bytes(), Path::new("map.tmx"), &mut images).is_none());
+        assert!(images.is_empty());
+    }
+}
@@ -0,0 +1,67 @@
+//! Apps loading maps without a window or a renderer, for tests and command line tools.
+//!
+//! Images are not loaded, their loader is only registered by the render plugins, so tilesets
+//! log an error for their textures but tiles, objects and properties are spawned as usual.
+
+use std::path::Path;
+
+use bevy::app::SubApp;
+use bevy::prelude::*;
+use bevy::render::RenderApp;
+use bevy::utils::{Duration, Instant};
+use bevy_ecs_tilemap::TilemapPlugin;
+
+use crate::{is_map_spawned, TiledBlueprintsPlugin};
+
+/// Creates an [`App`] with the [`MinimalPlugins`], an [`AssetPlugin`] reading from
+/// `asset_folder`, the [`TilemapPlugin`] and the [`TiledBlueprintsPlugin`].
+///
+/// The [`TilemapPlugin`] registers its render systems in a render sub-app, which is only given
+/// an empty one that is never updated, so tilemaps are updated like in a windowed app but not
+/// drawn. Components set in properties still have to be registered, like in any other app.
+pub fn spawn_test_app(asset_folder: impl AsRef<Path>) -> App {
+    spawn_test_app_with_plugin(asset_folder, TiledBlueprintsPlugin::default())
+}
+
+/// Creates an [`App`] like [`spawn_test_app`] with the given [`TiledBlueprintsPlugin`], for
+/// example to load maps with a [`TiledLoader`](crate::TiledLoader) reading an archive.
+pub fn spawn_test_app_with_plugin(
+    asset_folder: impl AsRef<Path>,
+    plugin: TiledBlueprintsPlugin,
+) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin {
+            file_path: asset_folder.as_ref().to_string_lossy().into_owned(),
+            ..Default::default()
+        },
+        HierarchyPlugin,
+        TransformPlugin,
+    ))
+    .init_asset::<Image>()
+    .init_asset::<Font>()
+    .init_asset::<Shader>()
+    .insert_sub_app(RenderApp, SubApp::new());
+    app.add_plugins((TilemapPlugin, plugin));
+    app.remove_sub_app(RenderApp);
+    app
+}
+
+/// Updates the app until the map of `map_entity` is spawned, see [`is_map_spawned`].
+///
+/// Assets are loaded on other threads, so the app is updated for at most `timeout`. Returns
+/// `false` if the map was not spawned in time, for example because it failed to load.
+pub fn update_until_spawned(app: &mut App, map_entity: Entity, timeout: Duration) -> bool {
+    let start = Instant::now();
+    loop {
+        app.update();
+        if is_map_spawned(app.world(), map_entity) {
+            return true;
+        }
+        if start.elapsed() > timeout {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
@@ -1,52 +1,171 @@
 use core::ops::Deref;
-use std::io::{Cursor, ErrorKind};
-use std::path::Path;
+use std::any::TypeId;
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use bevy::core::Name;
 use bevy::ecs::query::With;
-use bevy::ecs::reflect::{AppTypeRegistry, ReflectCommandExt, ReflectComponent};
+use bevy::ecs::reflect::{AppTypeRegistry, ReflectCommandExt, ReflectComponent, ReflectResource};
 use bevy::ecs::schedule::IntoSystemConfigs;
-use bevy::ecs::world::World;
+use bevy::ecs::system::{EntityCommands, SystemParam};
+use bevy::ecs::world::{CommandQueue, World};
 use bevy::math::Vec3;
-use bevy::reflect::{Reflect, TypeInfo};
+use bevy::reflect::{DynamicStruct, DynamicTupleStruct, Reflect, TypeInfo, TypeRegistration};
 use bevy::{
     asset::{io::Reader, AssetLoader, AssetPath, AsyncReadExt},
+    ecs::entity::Entities,
     log,
     prelude::*,
     reflect::{serde::ReflectDeserializer, TypePath, TypeRegistry},
-    utils::{BoxedFuture, HashMap},
+    utils::{Entry, HashMap, HashSet},
 };
 use bevy_ecs_tilemap::prelude::*;
 use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
 
 use thiserror::Error;
 
+mod archive;
+#[cfg(feature = "avian")]
+pub mod avian;
 pub mod debug;
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+mod embedded;
+pub mod headless;
+pub mod neighbors;
+mod orders;
+mod project;
+#[cfg(feature = "rapier")]
+pub mod rapier;
+pub mod shapes;
+mod tmj;
+pub mod world;
 
 pub mod prelude {
     pub use super::{
-        debug::TiledBlueprintsDebugDisplayPlugin, RemoveMap, TiledBlueprintsPlugin,
-        TiledLayersStorage, TiledMap, TiledMapBundle,
+        debug::{TiledBlueprintsDebugDisplayPlugin, TiledDebugConfig},
+        neighbors::{tile_neighbor, TileDirection},
+        objects_with_class,
+        shapes::{TileColliderChildren, TileColliders, TiledObjectShape, TiledObjectSize},
+        world::{TiledWorld, TiledWorldBundle, TiledWorldSettings},
+        DespawnTiledMap, ObjectContext, ObjectDrawOrder, ObjectLayer, ParallaxFactor,
+        RegisterTiledClass, RegisterTiledMaterial, ReloadTiledMaps, RemoveMap, RenderOrder,
+        SetTiledPropertyHandler, SkippedLayer, TileLayerMarker, TileObjectAnchor, TileSource,
+        TileWangId, TiledBlueprintsPlugin, TiledInsertedComponents, TiledLayersStorage, TiledMap,
+        TiledMapAnchor, TiledMapBundle, TiledMapCamera, TiledMapInfo, TiledMapPath,
+        TiledMapSettings, TiledMapSpawned, TiledMaps, TiledObject, TiledObjectHook,
+        TiledObjectLayer, TiledOrientation, TiledSystems, TiledTileset, YAxis,
     };
     pub use bevy_ecs_tilemap;
 }
 
-pub struct TiledBlueprintsPlugin;
+#[derive(Default)]
+pub struct TiledBlueprintsPlugin {
+    /// Sets the [`ClearColor`] to the background color of the spawned map.
+    /// The previous clear color is restored once all maps are removed.
+    pub apply_background_color: bool,
+    /// Moves layers with a [`ParallaxFactor`] relative to the position of the 2D camera.
+    pub apply_parallax: bool,
+    /// Frames the cameras marked with [`TiledMapCamera`] to every spawned map.
+    pub fit_camera: bool,
+    /// Called for every spawned object, inserted as the [`TiledObjectHook`] resource.
+    pub object_hook: Option<TiledObjectHook>,
+    /// Loader of the maps, created with [`TiledLoader::with_archive`] to read the files of maps
+    /// from an archive.
+    pub loader: TiledLoader,
+}
+
+impl TiledBlueprintsPlugin {
+    /// Sets the [`TiledObjectHook`] called for every spawned object.
+    pub fn with_object_hook(
+        mut self,
+        hook: impl Fn(&mut EntityCommands, &ObjectContext) + Send + Sync + 'static,
+    ) -> Self {
+        self.object_hook = Some(TiledObjectHook(Arc::new(hook)));
+        self
+    }
+}
 
 impl Plugin for TiledBlueprintsPlugin {
     fn build(&self, app: &mut bevy::app::App) {
         app.init_asset::<TiledMap>()
-            .register_asset_loader(TiledLoader)
+            .register_asset_loader(self.loader.clone())
             .register_type::<RemoveMap>()
             .register_type::<MapObject>()
+            .register_type::<TiledObject>()
+            .register_type::<TiledObjectLayer>()
+            .register_type::<TileSource>()
+            .register_type::<TileWangId>()
+            .register_type::<ObjectLayer>()
+            .register_type::<ObjectDrawOrder>()
+            .register_type::<RenderOrder>()
+            .register_type::<TileLayerMarker>()
             .register_type::<TiledLayersStorage>()
-            .add_systems(Update, (process_loaded_maps, cleanup_maps).chain());
+            .register_type::<TiledMapAnchor>()
+            .register_type::<TiledMapInfo>()
+            .register_type::<ParallaxFactor>()
+            .register_type::<TiledMapCamera>()
+            .register_type::<TiledMapPath>()
+            .register_type::<SkippedLayer>()
+            .register_type::<TiledTileset>()
+            .register_type::<shapes::TiledObjectShape>()
+            .register_type::<shapes::TiledObjectSize>()
+            .register_type::<shapes::TileColliders>()
+            .register_type::<shapes::TileColliderChildren>()
+            .add_event::<TiledMapSpawned>()
+            .add_event::<ReloadTiledMaps>()
+            .init_resource::<TiledClassRegistry>()
+            .init_resource::<TiledMaterialRegistry>()
+            .configure_sets(
+                Update,
+                (TiledSystems::Process, TiledSystems::Cleanup).chain(),
+            )
+            .add_systems(
+                Update,
+                (load_map_paths, process_loaded_maps)
+                    .chain()
+                    .in_set(TiledSystems::Process),
+            )
+            .add_systems(Update, cleanup_maps.in_set(TiledSystems::Cleanup));
+        world::register(app);
+        #[cfg(feature = "avian")]
+        avian::register(app);
+        #[cfg(feature = "rapier")]
+        rapier::register(app);
+        #[cfg(feature = "diagnostics")]
+        diagnostics::register(app);
+        if let Some(object_hook) = &self.object_hook {
+            app.insert_resource(object_hook.clone());
+        }
+        if self.apply_background_color {
+            app.add_systems(Update, apply_background_color.after(TiledSystems::Process));
+        }
+        if self.apply_parallax {
+            app.add_systems(Update, apply_parallax.after(TiledSystems::Process));
+        }
+        if self.fit_camera {
+            app.add_systems(Update, fit_camera_to_map.after(TiledSystems::Process));
+        }
     }
 }
 
+/// Sets of the systems added by [`TiledBlueprintsPlugin`] in [`Update`], in this order.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TiledSystems {
+    /// Loads [`TiledMapPath`] components and spawns loaded or changed maps with
+    /// [`process_loaded_maps`].
+    Process,
+    /// Despawns removed maps with [`cleanup_maps`].
+    Cleanup,
+}
+
 #[derive(TypePath, Asset)]
 pub struct TiledMap {
+    /// The parsed map. The size of infinite maps is the bounding box of the populated chunks of
+    /// their tile layers.
     pub map: tiled::Map,
 
     pub tilemap_textures: HashMap<usize, TilemapTexture>,
@@ -54,492 +173,4459 @@ pub struct TiledMap {
     /// The offset into the tileset_images for each tile id within each tileset.
     #[cfg(not(feature = "atlas"))]
     pub tile_image_offsets: HashMap<(usize, tiled::TileId), u32>,
-}
-
-/// Stores a list of tiled layers.
-#[derive(Debug, Reflect, Component, Default, Clone)]
-#[reflect(Component)]
-pub struct TiledLayersStorage {
-    pub storage: HashMap<u32, Entity>,
-}
-
-#[derive(Default, Bundle)]
-pub struct TiledMapBundle {
-    pub tiled_map: Handle<TiledMap>,
-    pub storage: TiledLayersStorage,
-    pub transform: Transform,
-    pub global_transform: GlobalTransform,
-}
-
-#[derive(Debug, Reflect, Component, Default, Clone)]
-#[reflect(Component)]
-pub struct RemoveMap;
 
-#[derive(Debug, Reflect, Component, Default, Clone)]
-#[reflect(Component)]
-pub struct MapObject;
+    /// The size of the image of each tile within each image collection tileset,
+    /// as declared in the tileset.
+    #[cfg(not(feature = "atlas"))]
+    pub tile_image_sizes: HashMap<(usize, tiled::TileId), UVec2>,
 
-struct BytesResourceReader {
-    bytes: Arc<[u8]>,
-}
+    /// The image of each image layer, by the id of the layer.
+    pub image_layer_textures: HashMap<u32, Handle<Image>>,
 
-impl BytesResourceReader {
-    fn new(bytes: &[u8]) -> Self {
-        Self {
-            bytes: Arc::from(bytes),
-        }
-    }
-}
+    /// The default values of the members of each class of the project set with
+    /// [`TiledLoader::with_project`], by class name.
+    pub class_defaults: HashMap<String, tiled::Properties>,
 
-impl tiled::ResourceReader for BytesResourceReader {
-    type Resource = Cursor<Arc<[u8]>>;
-    type Error = std::io::Error;
+    /// The order in which Tiled draws the tiles of the map, followed by isometric layers whose
+    /// tiles overlap, see [`TiledMap::render_order`].
+    pub render_order: RenderOrder,
 
-    fn read_from(&mut self, _path: &Path) -> std::result::Result<Self::Resource, Self::Error> {
-        // In this case, the path is ignored because the byte data is already provided.
-        Ok(Cursor::new(self.bytes.clone()))
-    }
-}
+    /// The draw order of each object layer set in Tiled, by the id of the layer. Layers without
+    /// one use the default [`ObjectDrawOrder::TopDown`].
+    pub object_draw_orders: HashMap<u32, ObjectDrawOrder>,
 
-pub struct TiledLoader;
+    /// The settings the map was loaded with.
+    pub settings: TiledMapSettings,
 
-#[derive(Debug, Error)]
-pub enum TiledAssetLoaderError {
-    /// An [IO](std::io) Error
-    #[error("Could not load Tiled file: {0}")]
-    Io(#[from] std::io::Error),
+    /// Names of the layers, in the order of [`TiledMap::layer_names`].
+    layer_names: Vec<String>,
 }
 
-impl AssetLoader for TiledLoader {
-    type Asset = TiledMap;
-    type Settings = ();
-    type Error = TiledAssetLoaderError;
+impl TiledMap {
+    /// Parses a map in the TMX or TMJ format from memory, without the [`AssetServer`].
+    ///
+    /// External tilesets, templates and images are read from the file system, with paths
+    /// relative to the working directory, so only maps whose sources can be resolved that way
+    /// are supported. Images have to be PNG files, they are decoded and added to `images`.
+    pub fn from_bytes(
+        bytes: &[u8],
+        images: &mut Assets<Image>,
+    ) -> Result<TiledMap, TiledAssetLoaderError> {
+        let (map, embedded_images, orders) = parse_map(bytes, Path::new("map.tmx"), None, None)?;
+        let mut tiled_map = TiledMap::new(map, TiledMapSettings::default(), |path| {
+            let image = match embedded_images.get(path) {
+                Some(bytes) => decode_image(path, bytes)?,
+                None => decode_image(path, &std::fs::read(path)?)?,
+            };
+            Ok(images.add(image))
+        })?;
+        tiled_map.render_order = orders.render_order;
+        tiled_map.object_draw_orders = orders.object_draw_orders;
+        Ok(tiled_map)
+    }
 
-    async fn load<'a>(
-        &'a self,
-        reader: &'a mut Reader<'_>,
-        _settings: &'a Self::Settings,
-        load_context: &'a mut bevy::asset::LoadContext<'_>,
-    ) -> Result<Self::Asset, Self::Error> {
-        let mut bytes = Vec::new();
-        reader.read_to_end(&mut bytes).await?;
+    /// Returns the value of a custom property of the map, also when it is not a component.
+    pub fn property(&self, name: &str) -> Option<&tiled::PropertyValue> {
+        self.map.properties.get(name)
+    }
 
-        let mut loader = tiled::Loader::with_cache_and_reader(
-            tiled::DefaultResourceCache::new(),
-            BytesResourceReader::new(&bytes),
-        );
-        let map = loader.load_tmx_map(load_context.path()).map_err(|e| {
-            std::io::Error::new(ErrorKind::Other, format!("Could not load TMX map: {e}"))
-        })?;
+    /// Returns the names of the layers of the map, with the layers of groups following their
+    /// group, in the order of the indices of [`TiledLayersStorage::storage`].
+    pub fn layer_names(&self) -> impl Iterator<Item = &str> {
+        self.layer_names.iter().map(String::as_str)
+    }
 
+    /// Creates the textures of the tilesets of the map, `load_image` loads an image from its
+    /// normalized path. Images shared by several tilesets or layers are only loaded once.
+    fn new(
+        mut map: tiled::Map,
+        settings: TiledMapSettings,
+        mut load_image: impl FnMut(&Path) -> Result<Handle<Image>, TiledAssetLoaderError>,
+    ) -> Result<TiledMap, TiledAssetLoaderError> {
+        // Infinite maps only keep the size they were created with in Tiled, which can be empty.
+        if map.infinite() {
+            let size = infinite_map_bounds(&map).map_or(
+                TilemapSize {
+                    x: map.width,
+                    y: map.height,
+                },
+                |(_, size)| size,
+            );
+            map.width = size.x.max(1);
+            map.height = size.y.max(1);
+        }
+        let mut loaded_images = HashMap::<PathBuf, Handle<Image>>::default();
+        let mut load_image = |path: &Path| -> Result<Handle<Image>, TiledAssetLoaderError> {
+            if let Some(handle) = loaded_images.get(path) {
+                return Ok(handle.clone());
+            }
+            let handle = load_image(path)?;
+            loaded_images.insert(path.to_path_buf(), handle.clone());
+            Ok(handle)
+        };
         let mut tilemap_textures = HashMap::default();
+        #[cfg(not(feature = "atlas"))]
         let mut tile_image_offsets = HashMap::default();
+        #[cfg(not(feature = "atlas"))]
+        let mut tile_image_sizes = HashMap::default();
 
         for (tileset_index, tileset) in map.tilesets().iter().enumerate() {
             let tilemap_texture = match &tileset.image {
                 None => {
+                    #[cfg(not(feature = "atlas"))]
                     {
                         let mut tile_images: Vec<Handle<Image>> = Vec::new();
                         for (tile_id, tile) in tileset.tiles() {
                             if let Some(img) = &tile.image {
-                                // The load context path is the TMX file itself. If the file is at the root of the
-                                // assets/ directory structure then the tmx_dir will be empty, which is fine.
-                                let tmx_dir = load_context
-                                    .path()
-                                    .parent()
-                                    .expect("The asset load context was empty.");
-                                let tile_path = tmx_dir.join(&img.source);
-                                let asset_path = AssetPath::from(tile_path);
-                                log::info!("Loading tile image from {asset_path:?} as image ({tileset_index}, {tile_id})");
-                                let texture: Handle<Image> = load_context.load(asset_path.clone());
+                                // Image paths are already relative to the file containing the
+                                // tileset, either the TMX file or an external tileset.
+                                let path = normalize_path(&img.source);
+                                log::info!("Loading tile image from {path:?} as image ({tileset_index}, {tile_id})");
+                                let texture = load_image(&path)?;
                                 tile_image_offsets
                                     .insert((tileset_index, tile_id), tile_images.len() as u32);
-                                tile_images.push(texture.clone());
+                                tile_image_sizes.insert(
+                                    (tileset_index, tile_id),
+                                    UVec2::new(img.width as u32, img.height as u32),
+                                );
+                                tile_images.push(texture);
                             }
                         }
 
                         TilemapTexture::Vector(tile_images)
                     }
+                    // Texture atlases have no support for tiles with their own images.
+                    #[cfg(feature = "atlas")]
+                    {
+                        log::warn!(
+                            "Skipping image collection tileset {}, which is not supported with the atlas feature",
+                            tileset.name
+                        );
+                        continue;
+                    }
                 }
-                Some(img) => {
-                    // The load context path is the TMX file itself. If the file is at the root of the
-                    // assets/ directory structure then the tmx_dir will be empty, which is fine.
-                    let tmx_dir = load_context
-                        .path()
-                        .parent()
-                        .expect("The asset load context was empty.");
-                    let tile_path = tmx_dir.join(&img.source);
-                    let asset_path = AssetPath::from(tile_path);
-                    let texture: Handle<Image> = load_context.load(asset_path.clone());
-
-                    TilemapTexture::Single(texture.clone())
-                }
+                Some(img) => TilemapTexture::Single(load_image(&normalize_path(&img.source))?),
             };
 
             tilemap_textures.insert(tileset_index, tilemap_texture);
         }
 
-        let asset_map = TiledMap {
+        let mut image_layer_textures = HashMap::default();
+        let mut layers = Vec::new();
+        flatten_layers(map.layers(), None, &mut layers);
+        // The wrappers of the `tiled` crate only lend their data for their own lifetime.
+        let layer_names = layers.iter().map(|(layer, _)| layer.name.clone()).collect();
+        for (layer, _) in layers {
+            if let tiled::LayerType::Image(image_layer) = layer.layer_type() {
+                if let Some(img) = &image_layer.image {
+                    let texture = load_image(&normalize_path(&img.source))?;
+                    image_layer_textures.insert(layer.id(), texture);
+                }
+            }
+        }
+
+        Ok(TiledMap {
             map,
             tilemap_textures,
+            #[cfg(not(feature = "atlas"))]
             tile_image_offsets,
-        };
-
-        log::info!("Loaded map: {}", load_context.path().display());
-        Ok(asset_map)
+            #[cfg(not(feature = "atlas"))]
+            tile_image_sizes,
+            image_layer_textures,
+            class_defaults: HashMap::default(),
+            render_order: RenderOrder::default(),
+            object_draw_orders: HashMap::default(),
+            settings,
+            layer_names,
+        })
     }
+}
 
-    fn extensions(&self) -> &[&str] {
-        static EXTENSIONS: &[&str] = &["tmx"];
-        EXTENSIONS
+/// Reads the [`TiledMap`] of map entities in systems, instead of getting their handle and then
+/// the asset.
+#[derive(SystemParam)]
+pub struct TiledMaps<'w, 's> {
+    handles: Query<'w, 's, &'static Handle<TiledMap>>,
+    maps: Res<'w, Assets<TiledMap>>,
+}
+
+impl TiledMaps<'_, '_> {
+    /// Returns the map of a map entity, or `None` if it has no map or the map is not loaded.
+    pub fn get_map(&self, entity: Entity) -> Option<&TiledMap> {
+        self.maps.get(self.handles.get(entity).ok()?)
     }
 }
 
-pub fn cleanup_maps(mut commands: Commands, q: Query<Entity, With<RemoveMap>>) {
-    for e in q.iter() {
-        commands.entity(e).despawn_recursive();
+/// Settings of the [`TiledLoader`], passed with [`AssetServer::load_with_settings`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TiledMapSettings {
+    /// Spawns the objects that display a tile.
+    pub spawn_tile_objects: bool,
+    /// Generates colliders from the collision shapes of tiles.
+    pub generate_colliders: bool,
+    /// Spawns the colliders of tiles as child entities, one per collision shape placed at its
+    /// offset in the tile, instead of a compound collider on the tile, with the `avian` and
+    /// `rapier` features. The tile keeps the rigid body.
+    pub colliders_as_children: bool,
+    /// Point of the sprites of tile objects placed at the position of the object, unless
+    /// overridden by their `anchor` property.
+    pub tile_object_anchor: TileObjectAnchor,
+    /// Inserts a [`TileWangId`] into tiles that are part of a Wang set.
+    pub wang_ids: bool,
+    /// Inserts a [`TiledObjectLayer`] into objects, with the entity of their layer.
+    pub object_layers: bool,
+    /// Spawns a [`Text2dBundle`] as a child of text objects.
+    /// Loading their `font` property requires the `TextPlugin`, so it can be disabled in headless
+    /// apps.
+    pub spawn_text_objects: bool,
+    /// Direction of the Y axis in the space of the map, see [`YAxis`].
+    pub y_axis: YAxis,
+}
+
+impl Default for TiledMapSettings {
+    fn default() -> Self {
+        Self {
+            spawn_tile_objects: true,
+            generate_colliders: false,
+            colliders_as_children: true,
+            tile_object_anchor: TileObjectAnchor::default(),
+            wang_ids: false,
+            object_layers: false,
+            spawn_text_objects: true,
+            y_axis: YAxis::default(),
+        }
     }
 }
 
-pub fn process_loaded_maps(
-    mut commands: Commands,
-    mut map_events: EventReader<AssetEvent<TiledMap>>,
-    maps: Res<Assets<TiledMap>>,
-    tile_storage_query: Query<(Entity, &TileStorage)>,
-    mut map_query: Query<(&Handle<TiledMap>, &mut TiledLayersStorage, Entity)>,
-    new_maps: Query<&Handle<TiledMap>, Added<Handle<TiledMap>>>,
-    type_registry: Res<AppTypeRegistry>,
-) {
-    let mut changed_maps = Vec::<AssetId<TiledMap>>::default();
-    for event in map_events.read() {
-        match event {
-            AssetEvent::Added { id } => {
-                log::info!("Map added!");
-                changed_maps.push(*id);
-            }
-            AssetEvent::Modified { id } => {
-                log::info!("Map changed!");
-                changed_maps.push(*id);
-            }
-            AssetEvent::Removed { id } => {
-                log::info!("Map removed!");
-                // if mesh was modified and removed in the same update, ignore the modification
-                // events are ordered so future modification events are ok
-                changed_maps.retain(|changed_handle| changed_handle == id);
+/// Direction of the Y axis in the space of a map, set with [`TiledMapSettings::y_axis`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum YAxis {
+    /// Converts the Y axis of Tiled, pointing down, to the Y axis of Bevy, pointing up.
+    #[default]
+    YUp,
+    /// Keeps the coordinates of Tiled, for code sharing them with other tools:
+    /// - the [`Transform`] of objects, the offsets of layers and the positions of object and
+    ///   image layer children are the pixel coordinates of Tiled, with Y growing downwards,
+    /// - the points of [`TiledObjectShape`](shapes::TiledObjectShape)s keep their Y and object
+    ///   rotations stay clockwise,
+    /// - the corners of [`TiledMapAnchor`] are still the corners seen in Tiled.
+    ///
+    /// The map entity gets a negative Y scale, so the map is displayed upright and world
+    /// coordinates stay the ones of Bevy, pointing up. Tilemaps with a negative scale would not
+    /// be drawn, so tile layers are laid out like with [`YAxis::YUp`] and mirrored back by their
+    /// own [`Transform`]: the [`TilePos`] of tiles and the positions of tile colliders keep the
+    /// Y axis of Bevy, the bottom row being 0.
+    YDown,
+}
+
+/// Point of the sprite of a tile object placed at the position of the object.
+///
+/// Set for all objects with [`TiledMapSettings::tile_object_anchor`] or for a single object with
+/// a string `anchor` property, one of `bottom_left`, `center` or `top_left`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TileObjectAnchor {
+    /// The bottom-left corner, like in Tiled.
+    #[default]
+    BottomLeft,
+    Center,
+    TopLeft,
+}
+
+impl TileObjectAnchor {
+    fn of_object(obj: &tiled::Object, default: Self) -> Self {
+        let Some(tiled::PropertyValue::StringValue(anchor)) = obj.properties.get("anchor") else {
+            return default;
+        };
+        match anchor.to_lowercase().as_str() {
+            "bottom_left" => Self::BottomLeft,
+            "center" => Self::Center,
+            "top_left" => Self::TopLeft,
+            _ => {
+                log::warn!(
+                    "Unknown anchor {} of object {}, expected bottom_left, center or top_left",
+                    anchor,
+                    obj.id()
+                );
+                default
             }
-            _ => continue,
         }
     }
 
-    // If we have new map entities add them to the changed_maps list.
-    for new_map_handle in new_maps.iter() {
-        changed_maps.push(new_map_handle.id());
+    /// Center of a box of the given size placed by this anchor, with the Y axis pointing up.
+    fn box_center(self, size: Vec2) -> Vec2 {
+        match self {
+            Self::BottomLeft => size / 2.0,
+            Self::Center => Vec2::ZERO,
+            Self::TopLeft => Vec2::new(size.x, -size.y) / 2.0,
+        }
     }
-    let type_registry = type_registry.read();
 
-    for changed_map in changed_maps.iter() {
-        for (map_handle, mut layer_storage, map_entity) in map_query.iter_mut() {
-            // only deal with currently changed map
-            if map_handle.id() != *changed_map {
-                continue;
-            }
-            if let Some(tiled_map) = maps.get(map_handle) {
-                for layer_entity in layer_storage.storage.values() {
-                    if let Ok((_, layer_tile_storage)) = tile_storage_query.get(*layer_entity) {
-                        for tile in layer_tile_storage.iter().flatten() {
-                            commands.entity(*tile).despawn_recursive()
-                        }
-                    }
-                    commands.entity(*layer_entity).insert(RemoveMap);
-                    // commands.entity(*layer_entity).despawn_recursive();
-                }
+    /// Maps that keep the Y axis of Tiled are mirrored, so the top and bottom are swapped.
+    fn sprite_anchor(self, flip_y: bool) -> bevy::sprite::Anchor {
+        match (self, flip_y) {
+            (Self::Center, _) => bevy::sprite::Anchor::Center,
+            (Self::BottomLeft, true) | (Self::TopLeft, false) => bevy::sprite::Anchor::BottomLeft,
+            (Self::TopLeft, true) | (Self::BottomLeft, false) => bevy::sprite::Anchor::TopLeft,
+        }
+    }
+}
 
-                add_properties(
-                    &tiled_map.map.properties,
-                    map_entity,
-                    &type_registry,
-                    &mut commands,
-                );
+/// Stores a list of tiled layers.
+///
+/// Layers are keyed by their index in the depth-first order of the layer tree, so layers nested
+/// in groups come right after their group layer. For maps without group layers this is the same
+/// as the index of the layer in the map.
+#[derive(Debug, Reflect, Component, Default, Clone)]
+#[reflect(Component)]
+pub struct TiledLayersStorage {
+    pub storage: HashMap<u32, Entity>,
+    /// Layers keyed by their name. If several layers share a name, the first one is stored.
+    pub names: HashMap<String, Entity>,
+    /// [`TiledTileset`] entities keyed by the index of their tileset in the map.
+    pub tilesets: HashMap<usize, Entity>,
+}
 
-                // The TilemapBundle requires that all tile images come exclusively from a single
-                // tiled texture or from a Vec of independent per-tile images. Furthermore, all of
-                // the per-tile images must be the same size. Since Tiled allows tiles of mixed
-                // tilesets on each layer and allows differently-sized tile images in each tileset,
-                // this means we need to load each combination of tileset and layer separately.
-                for (tileset_index, tileset) in tiled_map.map.tilesets().iter().enumerate() {
-                    let Some(tilemap_texture) = tiled_map.tilemap_textures.get(&tileset_index)
-                    else {
-                        log::warn!("Skipped creating layer with missing tilemap textures.");
-                        continue;
-                    };
+impl TiledLayersStorage {
+    /// Returns the entity of the layer with the given name.
+    pub fn layer_by_name(&self, name: &str) -> Option<Entity> {
+        self.names.get(name).copied()
+    }
+}
 
-                    let tile_size = TilemapTileSize {
-                        x: tileset.tile_width as f32,
-                        y: tileset.tile_height as f32,
-                    };
+/// Path of a map to load, an alternative to the handle of [`TiledMapBundle`] for maps declared in
+/// scenes.
+///
+/// [`load_map_paths`] loads the map and inserts its handle with a [`TiledLayersStorage`]. The
+/// entity needs the components of a [`SpatialBundle`] for the map to be visible.
+#[derive(Debug, Reflect, Component, Default, Clone, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct TiledMapPath(pub String);
 
-                    let tile_spacing = TilemapSpacing {
-                        x: tileset.spacing as f32,
-                        y: tileset.spacing as f32,
-                    };
+#[derive(Default, Bundle)]
+pub struct TiledMapBundle {
+    pub tiled_map: Handle<TiledMap>,
+    pub storage: TiledLayersStorage,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub inherited_visibility: InheritedVisibility,
+    pub view_visibility: ViewVisibility,
+    pub anchor: TiledMapAnchor,
+}
 
-                    let map_size = TilemapSize {
-                        x: tiled_map.map.width,
-                        y: tiled_map.map.height,
-                    };
+impl TiledMapBundle {
+    pub fn new(tiled_map: Handle<TiledMap>) -> Self {
+        Self {
+            tiled_map,
+            ..Default::default()
+        }
+    }
 
-                    let grid_size = TilemapGridSize {
-                        x: tiled_map.map.tile_width as f32,
-                        y: tiled_map.map.tile_height as f32,
-                    };
+    /// Places the map with the given transform. Layers are children of the map entity, so the
+    /// whole map is moved, rotated and scaled with it, around its [`TiledMapAnchor`].
+    pub fn at(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+}
 
-                    let map_type = match tiled_map.map.orientation {
-                        tiled::Orientation::Hexagonal => TilemapType::Hexagon(HexCoordSystem::Row),
-                        tiled::Orientation::Isometric => {
-                            TilemapType::Isometric(IsoCoordSystem::Diamond)
-                        }
-                        tiled::Orientation::Staggered => {
-                            TilemapType::Isometric(IsoCoordSystem::Staggered)
-                        }
-                        tiled::Orientation::Orthogonal => TilemapType::Square,
-                    };
+/// Point of the map placed at the position of the map entity.
+#[derive(Debug, Reflect, Component, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum TiledMapAnchor {
+    #[default]
+    Center,
+    /// The top-left corner of the map, as seen in Tiled.
+    TopLeft,
+    /// The bottom-left corner of the map, as seen in Tiled.
+    BottomLeft,
+}
 
-                    // Once materials have been created/added we need to then create the layers.
-                    for (layer_index, layer) in tiled_map.map.layers().enumerate() {
-                        let offset_x = layer.offset_x;
-                        let offset_y = layer.offset_y;
-                        let center = get_tilemap_center_transform(
-                            &map_size,
-                            &grid_size,
-                            &map_type,
-                            layer_index as f32,
-                        ) * Transform::from_xyz(offset_x, -offset_y, -1.0);
-                        let layer_world_size = center.translation.abs() * 2.0;
-                        let layer_entity = commands
-                            .spawn(Name::new(format!("Layer-{}", layer.name)))
-                            .insert(TransformBundle::from_transform(center))
-                            .set_parent(map_entity)
-                            .id();
-
-                        add_properties(
-                            &layer.properties,
-                            layer_entity,
-                            &type_registry,
-                            &mut commands,
-                        );
+impl TiledMapAnchor {
+    /// Offset of the layers from the position they have when the map is centered.
+    fn offset(self, pixel_size: Vec2, flip_y: bool) -> Vec3 {
+        let half_size = pixel_size / 2.0;
+        // Without flipping, the top of the map in Tiled is at the bottom.
+        let top = if flip_y { -half_size.y } else { half_size.y };
+        match self {
+            TiledMapAnchor::Center => Vec3::ZERO,
+            TiledMapAnchor::TopLeft => Vec3::new(half_size.x, top, 0.0),
+            TiledMapAnchor::BottomLeft => Vec3::new(half_size.x, -top, 0.0),
+        }
+    }
+}
 
-                        if let tiled::LayerType::Objects(obj_layer) = layer.layer_type() {
-                            for obj in obj_layer.objects() {
-                                let pos = Vec3::new(obj.x, -obj.y + layer_world_size.y, 0.0);
-                                let name = Name::new(if obj.name.is_empty() {
-                                    "Object".to_string()
-                                } else {
-                                    obj.name.clone()
-                                });
-                                let e = commands
-                                    .spawn((
-                                        name,
-                                        TransformBundle::from_transform(
-                                            Transform::from_translation(pos),
-                                        ),
-                                        MapObject,
-                                    ))
-                                    .set_parent(layer_entity)
-                                    .id();
-                                add_properties(&obj.properties, e, &type_registry, &mut commands);
-                            }
+/// Dimensions of a spawned map, inserted on the map entity.
+#[derive(Debug, Reflect, Component, Default, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct TiledMapInfo {
+    /// Width of the map in tiles. Infinite maps cover the populated chunks of their tile layers.
+    pub width: u32,
+    /// Height of the map in tiles.
+    pub height: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// Size of the whole map in pixels, as shown in Tiled.
+    pub pixel_size: Vec2,
+    pub orientation: TiledOrientation,
+}
 
-                            layer_storage
-                                .storage
-                                .insert(layer_index as u32, layer_entity);
-                            continue;
-                        }
-                        let tiled::LayerType::Tiles(tile_layer) = layer.layer_type() else {
-                            log::info!(
-                                "Skipping layer {} because only tile layers are supported.",
-                                layer.id()
-                            );
-                            continue;
-                        };
+impl From<&tiled::Map> for TiledMapInfo {
+    fn from(map: &tiled::Map) -> Self {
+        Self {
+            width: map.width,
+            height: map.height,
+            tile_width: map.tile_width,
+            tile_height: map.tile_height,
+            pixel_size: map_pixel_size(map),
+            orientation: map.orientation.into(),
+        }
+    }
+}
 
-                        let tiled::TileLayer::Finite(layer_data) = tile_layer else {
-                            log::info!(
-                                "Skipping layer {} because only finite layers are supported.",
-                                layer.id()
-                            );
-                            continue;
-                        };
+/// Order in which Tiled draws the tiles of a map, row by row.
+#[derive(Debug, Reflect, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RenderOrder {
+    /// Rows from the top to the bottom of the map, each from left to right.
+    #[default]
+    RightDown,
+    RightUp,
+    LeftDown,
+    LeftUp,
+}
 
-                        let mut tile_storage = TileStorage::empty(map_size);
+impl RenderOrder {
+    /// Returns `true` if lower rows are drawn over the rows above them.
+    fn is_down(self) -> bool {
+        matches!(self, RenderOrder::RightDown | RenderOrder::LeftDown)
+    }
+}
 
-                        for x in 0..map_size.x {
-                            for y in 0..map_size.y {
-                                // Transform TMX coords into bevy coords.
-                                let mapped_y = tiled_map.map.height - 1 - y;
+/// Orientation of a Tiled map.
+#[derive(Debug, Reflect, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TiledOrientation {
+    #[default]
+    Orthogonal,
+    Isometric,
+    Staggered,
+    Hexagonal,
+}
 
-                                let mapped_x = x as i32;
-                                let mapped_y = mapped_y as i32;
+impl From<tiled::Orientation> for TiledOrientation {
+    fn from(orientation: tiled::Orientation) -> Self {
+        match orientation {
+            tiled::Orientation::Orthogonal => TiledOrientation::Orthogonal,
+            tiled::Orientation::Isometric => TiledOrientation::Isometric,
+            tiled::Orientation::Staggered => TiledOrientation::Staggered,
+            tiled::Orientation::Hexagonal => TiledOrientation::Hexagonal,
+        }
+    }
+}
 
-                                let layer_tile = match layer_data.get_tile(mapped_x, mapped_y) {
-                                    Some(t) => t,
-                                    None => {
-                                        continue;
-                                    }
-                                };
-                                if tileset_index != layer_tile.tileset_index() {
-                                    continue;
-                                }
-                                let layer_tile_data =
-                                    match layer_data.get_tile_data(mapped_x, mapped_y) {
-                                        Some(d) => d,
-                                        None => {
-                                            continue;
-                                        }
-                                    };
-
-                                let texture_index = match tilemap_texture {
-                                    TilemapTexture::Single(_) => layer_tile.id(),
-                                    TilemapTexture::Vector(_) =>
-                                        *tiled_map.tile_image_offsets.get(&(tileset_index, layer_tile.id()))
-                                        .expect("The offset into to image vector should have been saved during the initial load."),
-                                    _ => unreachable!()
-                                };
+#[derive(Debug, Reflect, Component, Default, Clone)]
+#[reflect(Component)]
+pub struct RemoveMap;
 
-                                let tile_pos = TilePos { x, y };
-                                let tile_entity = commands
-                                    .spawn((
-                                        TileBundle {
-                                            position: tile_pos,
-                                            tilemap_id: TilemapId(layer_entity),
-                                            texture_index: TileTextureIndex(texture_index),
-                                            flip: TileFlip {
-                                                x: layer_tile_data.flip_h,
-                                                y: layer_tile_data.flip_v,
-                                                d: layer_tile_data.flip_d,
-                                            },
-                                            ..Default::default()
-                                        },
-                                        Name::new(format!("tile-{}x{}", x, y)),
-                                    ))
-                                    .id();
-                                commands.entity(tile_entity).set_parent(layer_entity);
-                                tile_storage.set(&tile_pos, tile_entity);
-                            }
-                        }
+#[derive(Debug, Reflect, Component, Default, Clone)]
+#[reflect(Component)]
+pub struct MapObject;
 
-                        commands.entity(layer_entity).insert(TilemapBundle {
-                            grid_size,
-                            size: map_size,
-                            storage: tile_storage,
-                            texture: tilemap_texture.clone(),
-                            tile_size,
-                            spacing: tile_spacing,
-                            transform: get_tilemap_center_transform(
-                                &map_size,
-                                &grid_size,
-                                &map_type,
-                                layer_index as f32,
-                            ) * Transform::from_xyz(offset_x, -offset_y, 0.0),
-                            map_type,
-                            ..Default::default()
-                        });
+/// Components inserted on the map entity from the map properties.
+///
+/// Used to remove the components of properties deleted from the map when it is reloaded.
+#[derive(Debug, Component, Default, Clone)]
+pub struct TiledInsertedComponents(pub Vec<TypeId>);
 
-                        layer_storage
-                            .storage
-                            .insert(layer_index as u32, layer_entity);
-                    }
-                }
-            }
-        }
-    }
+/// Callback invoked for every spawned object, after its components were inserted.
+///
+/// Allows customizing objects that do not map cleanly to components.
+#[derive(Resource, Clone)]
+pub struct TiledObjectHook(pub Arc<ObjectHookFn>);
+
+pub type ObjectHookFn = dyn Fn(&mut EntityCommands, &ObjectContext) + Send + Sync;
+
+/// The Tiled object passed to the [`TiledObjectHook`].
+pub struct ObjectContext<'a> {
+    pub id: u32,
+    pub name: &'a str,
+    pub class: &'a str,
+    pub properties: &'a tiled::Properties,
+    /// Position of the object relative to its layer.
+    pub position: Vec3,
 }
 
-const REMOVE_PREFIX: &str = "remove:";
+/// Marks the entities of object layers.
+#[derive(Debug, Reflect, Component, Default, Clone)]
+#[reflect(Component)]
+pub struct ObjectLayer;
 
-fn add_properties(
-    properties: &std::collections::HashMap<String, tiled::PropertyValue>,
-    e: Entity,
-    type_registry: &impl Deref<Target = TypeRegistry>,
+/// Order in which the objects of an object layer are drawn, set for each layer in Tiled.
+///
+/// Inserted on the entities of object layers, whose objects are placed along the Z axis between
+/// the Z of the layer and 0.5 above it following this order.
+#[derive(Debug, Reflect, Component, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum ObjectDrawOrder {
+    /// Objects lower on the screen are drawn in front of the others.
+    #[default]
+    TopDown,
+    /// Objects are drawn in the order of the layer, the last one in front.
+    Index,
+}
+
+/// Marks the entities of tile layers.
+///
+/// A tilemap has a single texture, so the tiles of the first tileset used by the layer are in the
+/// tilemap of the layer entity, and the tiles of the other tilesets in tilemaps of child entities
+/// named `Tiles-{tileset name}`, without this marker.
+#[derive(Debug, Reflect, Component, Default, Clone)]
+#[reflect(Component)]
+pub struct TileLayerMarker;
+
+/// A tileset of the map, spawned as a child of the map entity with the components of the custom
+/// properties of the tileset, for settings shared by its tiles.
+#[derive(Debug, Reflect, Component, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct TiledTileset {
+    /// Index of the tileset within the map, like in [`tiled::Map::tilesets`].
+    pub index: usize,
+}
+
+/// Marks the cameras framed to spawned maps by [`fit_camera_to_map`].
+#[derive(Debug, Reflect, Component, Default, Clone)]
+#[reflect(Component)]
+pub struct TiledMapCamera;
+
+/// Marks layers spawned without their content, with the reason why.
+///
+/// Every layer of the map gets an entity in [`TiledLayersStorage`], even when it has nothing to
+/// show.
+#[derive(Debug, Reflect, Component, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub enum SkippedLayer {
+    /// The tile layer has no tiles.
+    Empty,
+    /// The tilesets of the layer have no textures, like image collection tilesets with the
+    /// `atlas` feature.
+    MissingTextures,
+    /// The type of the layer is not supported.
+    Unsupported,
+}
+
+/// Parallax scrolling factor of a layer, combined with the factors of its group layers.
+///
+/// A factor of 1 moves the layer along with the map, lower factors make it move slower when the
+/// camera moves and 0 keeps it in place relative to the camera.
+#[derive(Debug, Reflect, Component, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct ParallaxFactor {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Default for ParallaxFactor {
+    fn default() -> Self {
+        Self { x: 1.0, y: 1.0 }
+    }
+}
+
+/// Offset applied to a layer by `apply_parallax` in the previous frame.
+#[derive(Component, Default)]
+struct ParallaxOffset(Vec2);
+
+/// Inserts the bundle registered for a Tiled class.
+pub type InsertTiledClass = fn(&mut EntityCommands);
+
+/// Bundles inserted into objects and tiles by their class, instead of a single component.
+///
+/// Registered with [`RegisterTiledClass::register_tiled_class`].
+#[derive(Resource, Default, Clone)]
+pub struct TiledClassRegistry(pub HashMap<String, InsertTiledClass>);
+
+/// Extension for [`App`] to register bundles for Tiled classes.
+pub trait RegisterTiledClass {
+    /// Inserts the default value of `B` into objects and tiles with the given class.
+    fn register_tiled_class<B: Bundle + Default>(&mut self, class: &str) -> &mut Self;
+}
+
+impl RegisterTiledClass for App {
+    fn register_tiled_class<B: Bundle + Default>(&mut self, class: &str) -> &mut Self {
+        fn insert_default<B: Bundle + Default>(entity: &mut EntityCommands) {
+            entity.insert(B::default());
+        }
+        self.world_mut()
+            .get_resource_or_insert_with(TiledClassRegistry::default)
+            .0
+            .insert(class.to_string(), insert_default::<B>);
+        self
+    }
+}
+
+/// Inserts the material of a tile layer.
+pub type InsertTiledMaterial = dyn Fn(&mut EntityCommands) + Send + Sync;
+
+/// Materials of tile layers, inserted instead of the default material by the `material` layer
+/// property.
+///
+/// Registered with [`RegisterTiledMaterial::register_tiled_material`].
+#[derive(Resource, Default)]
+pub struct TiledMaterialRegistry(pub HashMap<String, Box<InsertTiledMaterial>>);
+
+/// Extension for [`App`] to register materials for tile layers.
+pub trait RegisterTiledMaterial {
+    /// Calls `insert` on the tilemaps of tile layers with a `material` property set to `name`,
+    /// once their [`StandardTilemapMaterial`] was removed, to insert the handle of a
+    /// [`MaterialTilemap`] added with its [`MaterialTilemapPlugin`].
+    fn register_tiled_material(
+        &mut self,
+        name: &str,
+        insert: impl Fn(&mut EntityCommands) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl RegisterTiledMaterial for App {
+    fn register_tiled_material(
+        &mut self,
+        name: &str,
+        insert: impl Fn(&mut EntityCommands) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(TiledMaterialRegistry::default)
+            .0
+            .insert(name.to_string(), Box::new(insert));
+        self
+    }
+}
+
+/// Extension for [`Commands`] to despawn spawned maps.
+pub trait DespawnTiledMap {
+    /// Marks the map and all of its layers with [`RemoveMap`], they are despawned by
+    /// [`cleanup_maps`] together with their tiles and objects.
+    fn despawn_tiled_map(&mut self, map_entity: Entity);
+}
+
+impl DespawnTiledMap for Commands<'_, '_> {
+    fn despawn_tiled_map(&mut self, map_entity: Entity) {
+        self.add(move |world: &mut World| {
+            let Some(mut map) = world.get_entity_mut(map_entity) else {
+                return;
+            };
+            let layers: Vec<Entity> = map
+                .get::<TiledLayersStorage>()
+                .map(|storage| storage.storage.values().copied().collect())
+                .unwrap_or_default();
+            map.insert(RemoveMap);
+            for layer in layers {
+                if let Some(mut layer) = world.get_entity_mut(layer) {
+                    layer.insert(RemoveMap);
+                }
+            }
+        });
+    }
+}
+
+/// Identifies the Tiled object an entity was spawned from.
+#[derive(Debug, Reflect, Component, Default, Clone, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct TiledObject {
+    /// Id of the object, unique within the map.
+    pub id: u32,
+    /// Class of the object, empty if not set.
+    pub class: String,
+}
+
+/// Entity of the layer an object was spawned in, to group objects by layer in queries without
+/// going through their [`Parent`].
+///
+/// Inserted when enabled with [`TiledMapSettings::object_layers`].
+#[derive(Debug, Reflect, Component, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct TiledObjectLayer(pub Entity);
+
+/// Returns the entities of the objects of the given class, useful to find objects which have no
+/// component of their own, like `for spawn in objects_with_class(&objects, "SpawnPoint") { .. }`
+/// in a system taking `objects: Query<(Entity, &TiledObject)>`.
+pub fn objects_with_class<'a, I>(objects: I, class: &str) -> impl Iterator<Item = Entity> + 'a
+where
+    I: IntoIterator<Item = (Entity, &'a TiledObject)>,
+    I::IntoIter: 'a,
+{
+    let class = class.to_owned();
+    objects
+        .into_iter()
+        .filter(move |(_, object)| object.class == class)
+        .map(|(entity, _)| entity)
+}
+
+/// Identifies the tileset tile a tile entity displays.
+#[derive(Debug, Reflect, Component, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct TileSource {
+    /// Index of the tileset within the map, like in [`tiled::Map::tilesets`].
+    pub tileset_index: usize,
+    /// Id of the tile within its tileset.
+    pub tile_id: u32,
+}
+
+/// Wang colors of a tile, inserted when enabled with [`TiledMapSettings::wang_ids`].
+///
+/// The colors are listed clockwise from the top edge, alternating edges and corners, like in
+/// Tiled. 0 means no color. For tiles in several Wang sets, the first set of the tileset is used.
+#[derive(Debug, Reflect, Component, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct TileWangId(pub [u8; 8]);
+
+/// Sent once the layers and objects of a map were spawned, both on the initial load and after
+/// the map asset was modified.
+///
+/// The entities are spawned through [`Commands`], so systems reading this event have to run
+/// after [`TiledSystems::Process`] to see them.
+#[derive(Event, Debug, Clone)]
+pub struct TiledMapSpawned {
+    pub map_entity: Entity,
+    pub handle: Handle<TiledMap>,
+}
+
+/// Respawns every spawned map from its loaded asset when sent, like when the map file changed.
+///
+/// Properties and classes are parsed again, so components registered since the maps were spawned
+/// are inserted, without touching the map files.
+#[derive(Event, Debug, Clone, Copy, Default)]
+pub struct ReloadTiledMaps;
+
+/// Marks maps that were already checked for the background color.
+#[derive(Component)]
+struct BackgroundColorApplied;
+
+/// Marks maps whose layers and objects are spawned, removed while they are rebuilt or their
+/// handle was replaced by one that is still loading.
+#[derive(Component)]
+struct MapSpawned;
+
+/// Reads the map from memory and the files it references, like external tilesets and templates,
+/// through the [`LoadContext`](bevy::asset::LoadContext), which tracks them as dependencies of the
+/// map so changing them reloads it.
+///
+/// Without a [`LoadContext`](bevy::asset::LoadContext) the referenced files are read from the
+/// file system. Files found in the archive of the [`TiledLoader`] are read from it instead.
+///
+/// Images embedded in the map and its tilesets are replaced with files, whose bytes are collected
+/// in `embedded_images`.
+struct BytesResourceReader<'a, 'ctx> {
+    map_path: PathBuf,
+    bytes: Arc<[u8]>,
+    load_context: Option<&'a mut bevy::asset::LoadContext<'ctx>>,
+    archive: Option<&'a archive::Archive>,
+    embedded_images: embedded::EmbeddedImages,
+}
+
+impl<'a, 'ctx> BytesResourceReader<'a, 'ctx> {
+    fn new(
+        bytes: &[u8],
+        map_path: &Path,
+        load_context: Option<&'a mut bevy::asset::LoadContext<'ctx>>,
+        archive: Option<&'a archive::Archive>,
+    ) -> Self {
+        Self {
+            map_path: map_path.to_path_buf(),
+            bytes: Arc::from(bytes),
+            load_context,
+            archive,
+            embedded_images: HashMap::default(),
+        }
+    }
+
+    fn read_bytes(&mut self, path: &Path) -> std::io::Result<Arc<[u8]>> {
+        if path == self.map_path {
+            // The map itself was already read by the asset loader.
+            return Ok(self.bytes.clone());
+        }
+        if let Some(bytes) = self.archive.and_then(|archive| archive.get(path)) {
+            return Ok(bytes);
+        }
+        let Some(load_context) = self.load_context.as_mut() else {
+            return Ok(Arc::from(std::fs::read(path)?));
+        };
+        let asset_path = AssetPath::from(normalize_path(path))
+            .with_source(load_context.asset_path().source().clone_owned());
+        let bytes = bevy::tasks::block_on(load_context.read_asset_bytes(asset_path))
+            .map_err(std::io::Error::other)?;
+        Ok(Arc::from(bytes))
+    }
+}
+
+impl tiled::ResourceReader for BytesResourceReader<'_, '_> {
+    type Resource = Cursor<Arc<[u8]>>;
+    type Error = std::io::Error;
+
+    fn read_from(&mut self, path: &Path) -> std::result::Result<Self::Resource, Self::Error> {
+        let mut bytes = self.read_bytes(path)?;
+        // Tilesets and templates of JSON maps are JSON too, the map itself is already converted.
+        if path != self.map_path && tmj::is_json_document(path, &bytes) {
+            bytes = Arc::from(tmj::to_tmx(&bytes)?.into_bytes());
+        }
+        match embedded::extract_images(&bytes, path, &mut self.embedded_images) {
+            Some(document) => Ok(Cursor::new(Arc::from(document))),
+            None => Ok(Cursor::new(bytes)),
+        }
+    }
+}
+
+/// Parses a map in the TMX or TMJ format, the files it references are read with a
+/// [`BytesResourceReader`].
+///
+/// Returns the embedded images of the map and its tilesets along with it, by their path.
+fn parse_map(
+    bytes: &[u8],
+    map_path: &Path,
+    load_context: Option<&mut bevy::asset::LoadContext>,
+    archive: Option<&archive::Archive>,
+) -> Result<(tiled::Map, embedded::EmbeddedImages, orders::MapOrders), TiledAssetLoaderError> {
+    let tmx_bytes;
+    let bytes = if tmj::is_json_document(map_path, bytes) {
+        tmx_bytes = tmj::to_tmx(bytes)?.into_bytes();
+        &tmx_bytes
+    } else {
+        bytes
+    };
+
+    let mut loader = tiled::Loader::with_cache_and_reader(
+        tiled::DefaultResourceCache::new(),
+        BytesResourceReader::new(bytes, map_path, load_context, archive),
+    );
+    let map = loader.load_tmx_map(map_path)?;
+    let (_, reader) = loader.into_inner();
+    Ok((map, reader.embedded_images, orders::read_orders(bytes)))
+}
+
+/// Decodes an image read without the [`AssetServer`], only PNG images are supported.
+fn decode_image(path: &Path, bytes: &[u8]) -> Result<Image, TiledAssetLoaderError> {
+    let image = image::load_from_memory(bytes).map_err(|source| TiledAssetLoaderError::Image {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(Image::from_dynamic(
+        image,
+        true,
+        bevy::render::render_asset::RenderAssetUsages::default(),
+    ))
+}
+
+/// Resolves the `..` and `.` components of a path, so the same file always has the same asset path.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Loads [`TiledMap`] assets from TMX and TMJ files.
+#[derive(Default, Clone)]
+pub struct TiledLoader {
+    archive: Option<Arc<archive::Archive>>,
+    project: Option<PathBuf>,
+}
+
+impl TiledLoader {
+    /// Reads the tilesets, templates and images referenced by maps from an uncompressed tar
+    /// archive instead of the asset source, which is still used for the maps themselves and for
+    /// files missing from the archive.
+    ///
+    /// Paths in the archive are relative to the assets folder, like asset paths. Bundling the
+    /// files of maps avoids a request for each of them on the web. Images have to be PNG files.
+    pub fn with_archive(bytes: &[u8]) -> Result<Self, TiledAssetLoaderError> {
+        Ok(Self {
+            archive: Some(Arc::new(archive::Archive::from_tar(bytes)?)),
+            project: None,
+        })
+    }
+
+    /// Reads the custom classes of a Tiled project file (`.tiled-project`) at the given asset
+    /// path, so objects get the default values of the members of their class that they do not
+    /// override, as shown in Tiled.
+    pub fn with_project(mut self, path: impl Into<PathBuf>) -> Self {
+        self.project = Some(path.into());
+        self
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TiledAssetLoaderError {
+    /// An [IO](std::io) Error
+    #[error("Could not load Tiled file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The map, or a tileset or template it references, is not valid.
+    #[error("Could not parse Tiled map: {0}")]
+    Parse(#[from] tiled::Error),
+    /// The JSON of a TMJ map is not valid.
+    #[error("Could not parse TMJ map: {0}")]
+    Json(#[from] serde_json::Error),
+    /// An image used by a map loaded with [`TiledMap::from_bytes`] or read from the archive of the
+    /// [`TiledLoader`] could not be decoded.
+    #[error("Could not decode image {path:?}: {source}")]
+    Image {
+        path: PathBuf,
+        source: image::ImageError,
+    },
+}
+
+impl AssetLoader for TiledLoader {
+    type Asset = TiledMap;
+    type Settings = TiledMapSettings;
+    type Error = TiledAssetLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        settings: &'a Self::Settings,
+        load_context: &'a mut bevy::asset::LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let map_path = load_context.path().to_path_buf();
+        let archive = self.archive.as_deref();
+        let class_defaults = match &self.project {
+            Some(project_path) => {
+                let bytes = match archive.and_then(|archive| archive.get(project_path)) {
+                    Some(bytes) => bytes.to_vec(),
+                    None => load_context
+                        .read_asset_bytes(project_path.clone())
+                        .await
+                        .map_err(std::io::Error::other)?,
+                };
+                let map_dir = map_path.parent().unwrap_or(Path::new(""));
+                project::class_defaults(&bytes, project_path, map_dir)?
+            }
+            None => HashMap::default(),
+        };
+        let (map, embedded_images, orders) =
+            parse_map(&bytes, &map_path, Some(load_context), archive)?;
+        let mut asset_map = TiledMap::new(map, settings.clone(), |path| {
+            let bytes = embedded_images.get(path).cloned();
+            if let Some(bytes) = bytes.or_else(|| archive.and_then(|archive| archive.get(path))) {
+                let image = decode_image(path, &bytes)?;
+                return Ok(load_context.add_labeled_asset(path.to_string_lossy().into(), image));
+            }
+            Ok(load_context.load(AssetPath::from(path.to_path_buf())))
+        })?;
+        asset_map.class_defaults = class_defaults;
+        asset_map.render_order = orders.render_order;
+        asset_map.object_draw_orders = orders.object_draw_orders;
+
+        log::info!("Loaded map: {}", load_context.path().display());
+        Ok(asset_map)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        static EXTENSIONS: &[&str] = &["tmx", "tmj", "json"];
+        EXTENSIONS
+    }
+}
+
+/// Loads the maps of entities with a [`TiledMapPath`] and no handle yet.
+pub fn load_map_paths(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    q: Query<(Entity, &TiledMapPath), Without<Handle<TiledMap>>>,
+) {
+    for (e, path) in q.iter() {
+        let handle: Handle<TiledMap> = asset_server.load(path.0.clone());
+        commands
+            .entity(e)
+            .insert((handle, TiledLayersStorage::default()));
+    }
+}
+
+/// Returns `true` once the layers and objects of the map were spawned.
+///
+/// That is the case from the frame in which [`TiledMapSpawned`] is sent for the map, once the
+/// commands of [`process_loaded_maps`] are applied. It is `false` again from the frame in which
+/// the map starts being respawned, after a reload or when its handle is replaced, until the new
+/// layers are spawned. Useful in tests and setup code waiting for a map, like
+/// `while !is_map_spawned(app.world(), map) { app.update(); }`.
+pub fn is_map_spawned(world: &World, map_entity: Entity) -> bool {
+    world.get::<MapSpawned>(map_entity).is_some()
+}
+
+/// Despawns the entities marked with [`RemoveMap`] and removes despawned layers from the
+/// [`TiledLayersStorage`] of maps.
+pub fn cleanup_maps(
+    mut commands: Commands,
+    q: Query<Entity, With<RemoveMap>>,
+    mut storages: Query<&mut TiledLayersStorage>,
+    entities: &Entities,
+) {
+    for e in q.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+    // Layers can also be despawned without their map, so the storage is checked every frame.
+    let removed = |e: &Entity| q.contains(*e) || !entities.contains(*e);
+    for mut storage in storages.iter_mut() {
+        if storage.storage.values().any(removed) {
+            storage.storage.retain(|_, e| !removed(e));
+            storage.names.retain(|_, e| !removed(e));
+        }
+        if storage.tilesets.values().any(removed) {
+            storage.tilesets.retain(|_, e| !removed(e));
+        }
+    }
+}
+
+fn apply_background_color(
+    mut commands: Commands,
+    mut clear_color: ResMut<ClearColor>,
+    mut previous_clear_color: Local<Option<ClearColor>>,
+    maps: Res<Assets<TiledMap>>,
+    new_maps: Query<(Entity, &Handle<TiledMap>), Without<BackgroundColorApplied>>,
+    applied_maps: Query<(), With<BackgroundColorApplied>>,
+    mut removed_maps: RemovedComponents<BackgroundColorApplied>,
+) {
+    for (map_entity, map_handle) in new_maps.iter() {
+        let Some(tiled_map) = maps.get(map_handle) else {
+            continue;
+        };
+        if let Some(c) = tiled_map.map.background_color {
+            previous_clear_color.get_or_insert_with(|| clear_color.clone());
+            clear_color.0 = Color::srgba_u8(c.red, c.green, c.blue, c.alpha);
+        }
+        commands.entity(map_entity).insert(BackgroundColorApplied);
+    }
+
+    if removed_maps.read().count() > 0 && applied_maps.is_empty() {
+        if let Some(previous) = previous_clear_color.take() {
+            *clear_color = previous;
+        }
+    }
+}
+
+/// Centers the cameras marked with [`TiledMapCamera`] on the maps from [`TiledMapSpawned`] and
+/// scales their [`OrthographicProjection`] so the whole map is visible.
+///
+/// Has to run after [`TiledSystems::Process`].
+pub fn fit_camera_to_map(
+    mut spawned_events: EventReader<TiledMapSpawned>,
+    map_query: Query<(&GlobalTransform, &TiledMapInfo, Option<&TiledMapAnchor>)>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<TiledMapCamera>>,
+) {
+    // The area of the projection is only updated at the end of the frame, so only the last map
+    // spawned in a frame is framed.
+    let Some((center, map_size)) = spawned_events
+        .read()
+        .filter_map(|event| {
+            let (map_transform, info, anchor) = map_query.get(event.map_entity).ok()?;
+            // The anchor offset moves the center of the map away from the map entity. Maps keeping
+            // the Y axis of Tiled may only be mirrored on the next frame, once their scale is
+            // propagated.
+            let center = map_transform.transform_point(
+                anchor
+                    .copied()
+                    .unwrap_or_default()
+                    .offset(info.pixel_size, !is_mirrored(map_transform)),
+            );
+            let scale = map_transform.compute_transform().scale.truncate().abs();
+            Some((center, info.pixel_size * scale))
+        })
+        .last()
+    else {
+        return;
+    };
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        transform.translation.x = center.x;
+        transform.translation.y = center.y;
+        let view_size = projection.area.size() / projection.scale;
+        let scale = (map_size / view_size).max_element();
+        if scale.is_finite() && scale > 0.0 {
+            projection.scale = scale;
+        }
+    }
+}
+
+/// Returns `true` if the transform mirrors its children, like the map entities of
+/// [`YAxis::YDown`] maps.
+pub(crate) fn is_mirrored(transform: &GlobalTransform) -> bool {
+    transform.affine().matrix3.determinant() < 0.0
+}
+
+/// Offsets the layers by the position of the 2D camera scaled by their [`ParallaxFactor`].
+///
+/// The position of the camera is taken relative to the world origin. Does nothing unless there
+/// is exactly one 2D camera.
+fn apply_parallax(
+    cameras: Query<&GlobalTransform, With<Camera2d>>,
+    mut layers: Query<(
+        &ParallaxFactor,
+        &mut ParallaxOffset,
+        &mut Transform,
+        &Parent,
+    )>,
+    factors: Query<&ParallaxFactor>,
+) {
+    let Ok(camera) = cameras.get_single() else {
+        return;
+    };
+    let camera = camera.translation().truncate();
+    for (factor, mut offset, mut transform, parent) in layers.iter_mut() {
+        // Group layers already moved by their own factor.
+        let parent_factor = factors.get(parent.get()).copied().unwrap_or_default();
+        let new_offset = camera * Vec2::new(parent_factor.x - factor.x, parent_factor.y - factor.y);
+        transform.translation += (new_offset - offset.0).extend(0.0);
+        offset.0 = new_offset;
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn process_loaded_maps(
+    mut commands: Commands,
+    mut map_events: EventReader<AssetEvent<TiledMap>>,
+    maps: Res<Assets<TiledMap>>,
+    tile_storage_query: Query<(Entity, &TileStorage)>,
+    mut map_query: Query<(
+        &Handle<TiledMap>,
+        &mut TiledLayersStorage,
+        Entity,
+        Option<&TiledMapAnchor>,
+        Option<&mut Transform>,
+    )>,
+    new_maps: Query<(Entity, &Handle<TiledMap>), Changed<Handle<TiledMap>>>,
+    type_registry: Res<AppTypeRegistry>,
+    class_registry: Res<TiledClassRegistry>,
+    material_registry: Res<TiledMaterialRegistry>,
+    asset_server: Res<AssetServer>,
+    mut spawned_events: EventWriter<TiledMapSpawned>,
+    mut reload_events: EventReader<ReloadTiledMaps>,
+    object_hook: Option<Res<TiledObjectHook>>,
+    #[cfg(feature = "diagnostics")] mut diagnostics: bevy::diagnostic::Diagnostics,
+) {
+    #[cfg(feature = "diagnostics")]
+    let (start, mut spawn_counts) = (
+        bevy::utils::Instant::now(),
+        diagnostics::SpawnCounts::default(),
+    );
+    let mut changed_maps = Vec::<AssetId<TiledMap>>::default();
+    for event in map_events.read() {
+        match event {
+            AssetEvent::Added { id } => {
+                log::info!("Map added!");
+                changed_maps.push(*id);
+            }
+            AssetEvent::Modified { id } => {
+                log::info!("Map changed!");
+                changed_maps.push(*id);
+            }
+            AssetEvent::Removed { id } => {
+                log::info!("Map removed!");
+                // if mesh was modified and removed in the same update, ignore the modification
+                // events are ordered so future modification events are ok
+                changed_maps.retain(|changed_handle| changed_handle == id);
+            }
+            _ => continue,
+        }
+    }
+
+    // If we have new map entities or replaced handles add them to the changed_maps list.
+    for (map_entity, new_map_handle) in new_maps.iter() {
+        // A replaced handle may still be loading, the map is no longer the one spawned.
+        commands.entity(map_entity).remove::<MapSpawned>();
+        changed_maps.push(new_map_handle.id());
+    }
+    if reload_events.read().count() > 0 {
+        for (map_handle, ..) in map_query.iter() {
+            if !changed_maps.contains(&map_handle.id()) {
+                changed_maps.push(map_handle.id());
+            }
+        }
+    }
+    let type_registry = type_registry.read();
+
+    for changed_map in changed_maps.iter() {
+        for (map_handle, mut layer_storage, map_entity, anchor, map_transform) in
+            map_query.iter_mut()
+        {
+            // only deal with currently changed map
+            if map_handle.id() != *changed_map {
+                continue;
+            }
+            if let Some(tiled_map) = maps.get(map_handle) {
+                // The old layers are despawned before the new ones are spawned, so systems
+                // running after `TiledSystems::Process` never see both of them.
+                for layer_entity in layer_storage.storage.values() {
+                    if let Ok((_, layer_tile_storage)) = tile_storage_query.get(*layer_entity) {
+                        for tile in layer_tile_storage.iter().flatten() {
+                            commands.entity(*tile).despawn_recursive()
+                        }
+                    }
+                    commands.entity(*layer_entity).despawn_recursive();
+                }
+                for tileset_entity in layer_storage.tilesets.values() {
+                    commands.entity(*tileset_entity).despawn_recursive();
+                }
+                layer_storage.storage.clear();
+                layer_storage.names.clear();
+                layer_storage.tilesets.clear();
+                commands.entity(map_entity).remove::<MapSpawned>();
+
+                let map_dir = tiled_map.map.source.parent().unwrap_or(Path::new(""));
+                let y_up = tiled_map.settings.y_axis == YAxis::YUp;
+                if let Some(mut map_transform) = map_transform {
+                    // Mirrors the map back upright when it keeps the Y axis of Tiled.
+                    if !y_up && map_transform.scale.y > 0.0 {
+                        map_transform.scale.y = -map_transform.scale.y;
+                    }
+                }
+                let anchor_offset = anchor
+                    .copied()
+                    .unwrap_or_default()
+                    .offset(map_pixel_size(&tiled_map.map), y_up);
+                // Infinite maps start at their first populated chunk.
+                let map_origin =
+                    infinite_map_bounds(&tiled_map.map).map_or(IVec2::ZERO, |(origin, _)| origin);
+                let infinite_offset = infinite_map_offset(&tiled_map.map, map_origin, y_up);
+                commands
+                    .entity(map_entity)
+                    .insert(TiledMapInfo::from(&tiled_map.map));
+                let map_properties = parse_properties(
+                    &tiled_map.map.properties,
+                    map_entity,
+                    map_dir,
+                    &type_registry,
+                );
+                let mut map_components: Vec<TypeId> = map_properties
+                    .iter()
+                    .filter_map(ParsedProperty::inserted_type)
+                    .collect();
+                for property in map_properties.iter() {
+                    property.apply(map_entity, &mut commands);
+                }
+                // Object properties are resolved after all objects are spawned.
+                let mut object_entities = HashMap::<u32, Entity>::default();
+                // Tiles sharing the same id only parse their properties once.
+                let mut tile_properties =
+                    HashMap::<(usize, tiled::TileId), Vec<ParsedProperty>>::default();
+                let mut tile_animations =
+                    HashMap::<(usize, tiled::TileId), Option<AnimatedTile>>::default();
+                let mut tile_colliders =
+                    HashMap::<(usize, tiled::TileId), Option<shapes::TileColliders>>::default();
+                let mut tile_classes =
+                    HashMap::<(usize, tiled::TileId), Option<InsertTiledClass>>::default();
+                let mut tile_wang_ids =
+                    HashMap::<(usize, tiled::TileId), Option<TileWangId>>::default();
+                let mut object_references =
+                    vec![(map_entity, object_values(&tiled_map.map.properties))];
+
+                for (tileset_index, tileset) in tiled_map.map.tilesets().iter().enumerate() {
+                    let tileset_entity = commands
+                        .spawn((
+                            Name::new(format!("Tileset-{}", tileset.name)),
+                            TiledTileset {
+                                index: tileset_index,
+                            },
+                            SpatialBundle::default(),
+                        ))
+                        .set_parent(map_entity)
+                        .id();
+                    add_properties(
+                        &tileset.properties,
+                        tileset_entity,
+                        map_dir,
+                        &type_registry,
+                        &mut commands,
+                    );
+                    object_references.push((tileset_entity, object_values(&tileset.properties)));
+                    layer_storage.tilesets.insert(tileset_index, tileset_entity);
+
+                    match tiled_map.tilemap_textures.get(&tileset_index) {
+                        None => log::warn!(
+                            "Skipped creating the layers of tileset {} with missing tilemap textures.",
+                            tileset.name
+                        ),
+                        #[cfg(not(feature = "atlas"))]
+                        Some(TilemapTexture::Vector(_)) => {
+                            warn_mixed_image_sizes(tiled_map, tileset_index, tileset)
+                        }
+                        Some(_) => {}
+                    }
+                }
+
+                // The TilemapBundle requires that all tile images come exclusively from a single
+                // tiled texture or from a Vec of independent per-tile images. Furthermore, all of
+                // the per-tile images must be the same size. Since Tiled allows tiles of mixed
+                // tilesets on each layer and allows differently-sized tile images in each tileset,
+                // this means the tiles of each tileset of a layer are spawned in their own tilemap.
+                let map_size = TilemapSize {
+                    x: tiled_map.map.width,
+                    y: tiled_map.map.height,
+                };
+
+                let grid_size = map_grid_size(&tiled_map.map);
+
+                let first_tile = IVec2::new(0, if y_up { map_size.y as i32 - 1 } else { 0 });
+                let map_type = tilemap_type(&tiled_map.map, first_tile, y_up);
+
+                let mut layers = Vec::new();
+                flatten_layers(tiled_map.map.layers(), None, &mut layers);
+                let mut layer_colors = Vec::with_capacity(layers.len());
+                let mut layer_parallax = Vec::with_capacity(layers.len());
+                let mut layer_flips = Vec::with_capacity(layers.len());
+                // The center of the map in the space of the children of each group layer.
+                let mut mirror_centers = Vec::with_capacity(layers.len());
+
+                // Once materials have been created/added we need to then create the layers.
+                for (layer_index, (layer, parent_group)) in layers.into_iter().enumerate() {
+                    let parent_color = parent_group
+                        .map(|group_index| layer_colors[group_index])
+                        .unwrap_or(Srgba::WHITE);
+                    let layer_color = layer_color(&layer, parent_color);
+                    layer_colors.push(layer_color);
+                    let parent_parallax: ParallaxFactor = parent_group
+                        .map(|group_index| layer_parallax[group_index])
+                        .unwrap_or_default();
+                    let parallax = ParallaxFactor {
+                        x: parent_parallax.x * layer.parallax_x,
+                        y: parent_parallax.y * layer.parallax_y,
+                    };
+                    layer_parallax.push(parallax);
+                    let parent_flip = parent_group
+                        .map(|group_index| layer_flips[group_index])
+                        .unwrap_or(BVec2::FALSE);
+                    let flip = parent_flip
+                        ^ BVec2::new(
+                            has_layer_flag(&layer, FLIP_X_PROPERTY),
+                            has_layer_flag(&layer, FLIP_Y_PROPERTY),
+                        );
+                    layer_flips.push(flip);
+                    let offset_x = layer.offset_x;
+                    let offset_y = if y_up {
+                        -layer.offset_y
+                    } else {
+                        layer.offset_y
+                    };
+                    let z_offset = layer_z_offset(&layer);
+                    let center = match tiled_map.map.orientation {
+                        tiled::Orientation::Staggered => staggered_center_transform(
+                            &tiled_map.map,
+                            layer_origin_in_pixels(&tiled_map.map, y_up),
+                            layer_index as f32 + z_offset,
+                            y_up,
+                        ),
+                        _ => get_tilemap_center_transform(
+                            &map_size,
+                            &grid_size,
+                            &map_type,
+                            layer_index as f32 + z_offset,
+                        ),
+                    } * Transform::from_xyz(offset_x, offset_y, -1.0);
+                    let center =
+                        Transform::from_translation(anchor_offset + infinite_offset) * center;
+                    // Group layers are spawned before their children, so the parent is
+                    // already stored.
+                    let parent_entity = parent_group
+                        .and_then(|group_index| {
+                            layer_storage.storage.get(&(group_index as u32)).copied()
+                        })
+                        .unwrap_or(map_entity);
+                    // Group layers only offset their children, which are already
+                    // positioned relative to the map.
+                    let mirror_center = parent_group
+                        .map(|group_index| mirror_centers[group_index])
+                        .unwrap_or(anchor_offset.truncate());
+                    mirror_centers.push(mirror_center - Vec2::new(offset_x, offset_y));
+                    let layer_transform = match layer.layer_type() {
+                        tiled::LayerType::Group(_) => {
+                            Transform::from_xyz(offset_x, offset_y, z_offset)
+                        }
+                        // Flipped tilemaps would be culled, so their tiles are mirrored instead.
+                        tiled::LayerType::Tiles(_) => center,
+                        _ => mirror_transform(center, flip, mirror_center),
+                    };
+                    // Hidden layers are still spawned so they can be shown at runtime.
+                    let visibility = if layer.visible {
+                        Visibility::Inherited
+                    } else {
+                        Visibility::Hidden
+                    };
+                    #[cfg(feature = "diagnostics")]
+                    {
+                        spawn_counts.layers += 1;
+                    }
+                    let layer_entity = commands
+                        .spawn(Name::new(format!("Layer-{}", layer.name)))
+                        .insert(TransformBundle::from_transform(layer_transform))
+                        .insert(VisibilityBundle {
+                            visibility,
+                            ..Default::default()
+                        })
+                        .insert((parallax, ParallaxOffset::default()))
+                        .set_parent(parent_entity)
+                        .id();
+                    match layer_storage.names.entry(layer.name.clone()) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(layer_entity);
+                        }
+                        Entry::Occupied(_) => {
+                            log::warn!(
+                                "Layer {} has the same name as another layer: {}",
+                                layer.id(),
+                                layer.name
+                            );
+                        }
+                    }
+
+                    add_properties(
+                        &layer.properties,
+                        layer_entity,
+                        map_dir,
+                        &type_registry,
+                        &mut commands,
+                    );
+                    object_references.push((layer_entity, object_values(&layer.properties)));
+
+                    if let tiled::LayerType::Group(_) = layer.layer_type() {
+                        layer_storage
+                            .storage
+                            .insert(layer_index as u32, layer_entity);
+                        continue;
+                    }
+
+                    if let tiled::LayerType::Objects(obj_layer) = layer.layer_type() {
+                        let draw_order = tiled_map
+                            .object_draw_orders
+                            .get(&layer.id())
+                            .copied()
+                            .unwrap_or_default();
+                        commands
+                            .entity(layer_entity)
+                            .insert((ObjectLayer, draw_order));
+                        let object_count = obj_layer.objects().len();
+                        for (object_index, obj) in obj_layer.objects().enumerate() {
+                            if !tiled_map.settings.spawn_tile_objects && obj.get_tile().is_some() {
+                                continue;
+                            }
+                            let z = match draw_order {
+                                ObjectDrawOrder::TopDown => object_z(&obj, &tiled_map.map),
+                                ObjectDrawOrder::Index => {
+                                    object_index as f32 / object_count as f32 * OBJECT_Z_RANGE
+                                }
+                            };
+                            // Shared by the sprite and the shape of tile objects.
+                            let tile_anchor = obj.get_tile().map(|_| {
+                                TileObjectAnchor::of_object(
+                                    &obj,
+                                    tiled_map.settings.tile_object_anchor,
+                                )
+                            });
+                            let pos = match map_type {
+                                TilemapType::Isometric(IsoCoordSystem::Diamond) => {
+                                    isometric_object_position(
+                                        Vec2::new(obj.x, obj.y),
+                                        &tiled_map.map,
+                                        &grid_size,
+                                        y_up,
+                                    )
+                                    .extend(z)
+                                }
+                                // Staggered and hexagonal maps store pixels, like orthogonal
+                                // ones.
+                                _ => pixel_position_in_layer(
+                                    Vec2::new(obj.x, obj.y),
+                                    &tiled_map.map,
+                                    y_up,
+                                )
+                                .extend(z),
+                            };
+                            let name = Name::new(if obj.name.is_empty() {
+                                "Object".to_string()
+                            } else {
+                                obj.name.clone()
+                            });
+                            let e = commands
+                                .spawn((
+                                    name,
+                                    TransformBundle::from_transform(
+                                        Transform::from_translation(pos)
+                                            .with_rotation(object_rotation(&obj, y_up)),
+                                    ),
+                                    // Hidden objects are still spawned, like hidden layers.
+                                    VisibilityBundle {
+                                        visibility: if obj.visible {
+                                            Visibility::Inherited
+                                        } else {
+                                            Visibility::Hidden
+                                        },
+                                        ..Default::default()
+                                    },
+                                    MapObject,
+                                    TiledObject {
+                                        id: obj.id(),
+                                        class: obj.user_type.clone(),
+                                    },
+                                    object_shape(&obj, tile_anchor, y_up),
+                                ))
+                                .set_parent(layer_entity)
+                                .id();
+                            if tiled_map.settings.object_layers {
+                                commands.entity(e).insert(TiledObjectLayer(layer_entity));
+                            }
+                            if let Some(size) = shapes::TiledObjectSize::from_shape(&obj.shape) {
+                                commands.entity(e).insert(size);
+                            }
+                            if let Some((mut sprite, texture)) = tile_object_sprite(tiled_map, &obj)
+                            {
+                                sprite.color = tile_object_color(&obj, layer_color).into();
+                                if !y_up {
+                                    sprite.flip_y = !sprite.flip_y;
+                                }
+                                sprite.anchor = tile_anchor.unwrap_or_default().sprite_anchor(y_up);
+                                commands.entity(e).insert((sprite, texture));
+                            }
+                            if tiled_map.settings.spawn_text_objects {
+                                if let Some(text) =
+                                    text_object_bundle(&obj, map_dir, &asset_server, y_up)
+                                {
+                                    commands.spawn((Name::new("Text"), text)).set_parent(e);
+                                }
+                            }
+                            if let Some(insert_class) = class_registry.0.get(&obj.user_type) {
+                                insert_class(&mut commands.entity(e));
+                            } else {
+                                add_class_component(
+                                    &obj.user_type,
+                                    e,
+                                    &type_registry,
+                                    &mut commands,
+                                );
+                            }
+                            // Members of the class not overridden by the object keep their
+                            // default value from the project.
+                            let properties = match tiled_map.class_defaults.get(&obj.user_type) {
+                                Some(defaults) => {
+                                    let mut properties = defaults.clone();
+                                    properties.extend(obj.properties.clone());
+                                    Cow::Owned(properties)
+                                }
+                                None => Cow::Borrowed(&obj.properties),
+                            };
+                            add_properties(&properties, e, map_dir, &type_registry, &mut commands);
+                            if let Some(object_hook) = &object_hook {
+                                let context = ObjectContext {
+                                    id: obj.id(),
+                                    name: &obj.name,
+                                    class: &obj.user_type,
+                                    properties: &properties,
+                                    position: pos,
+                                };
+                                (object_hook.0)(&mut commands.entity(e), &context);
+                            }
+                            object_entities.insert(obj.id(), e);
+                            #[cfg(feature = "diagnostics")]
+                            {
+                                spawn_counts.objects += 1;
+                            }
+                            object_references.push((e, object_values(&properties)));
+                        }
+
+                        layer_storage
+                            .storage
+                            .insert(layer_index as u32, layer_entity);
+                        continue;
+                    }
+                    if let tiled::LayerType::Image(_) = layer.layer_type() {
+                        if let Some(texture) = tiled_map.image_layer_textures.get(&layer.id()) {
+                            // Images start at the corner of the map.
+                            let corner = pixel_position_in_layer(Vec2::ZERO, &tiled_map.map, y_up);
+                            let anchor = if y_up {
+                                bevy::sprite::Anchor::TopLeft
+                            } else {
+                                bevy::sprite::Anchor::BottomLeft
+                            };
+                            commands
+                                .spawn((
+                                    Name::new("Image"),
+                                    SpriteBundle {
+                                        texture: texture.clone(),
+                                        sprite: Sprite {
+                                            color: layer_color.into(),
+                                            flip_y: !y_up,
+                                            anchor,
+                                            ..Default::default()
+                                        },
+                                        transform: Transform::from_translation(corner.extend(0.0)),
+                                        ..Default::default()
+                                    },
+                                ))
+                                .set_parent(layer_entity);
+                        }
+                        layer_storage
+                            .storage
+                            .insert(layer_index as u32, layer_entity);
+                        continue;
+                    }
+                    let tiled::LayerType::Tiles(tile_layer) = layer.layer_type() else {
+                        log::info!(
+                            "Skipping layer {} because only tile layers are supported.",
+                            layer.id()
+                        );
+                        commands
+                            .entity(layer_entity)
+                            .insert(SkippedLayer::Unsupported);
+                        layer_storage
+                            .storage
+                            .insert(layer_index as u32, layer_entity);
+                        continue;
+                    };
+                    commands.entity(layer_entity).insert(TileLayerMarker);
+                    // Tilemaps with a negative scale are culled, so tile layers are always laid
+                    // out with the Y axis of Bevy, which is why `true` is passed for it below, and
+                    // the tile layers of mirrored maps are mirrored back by their own transform.
+                    let tilemap_mirror = if y_up {
+                        Transform::IDENTITY
+                    } else {
+                        Transform::from_scale(Vec3::new(1.0, -1.0, 1.0))
+                    };
+                    let mirror_y = |v: Vec3| tilemap_mirror.transform_point(v);
+                    let (offset_y, anchor_offset, infinite_offset, mirror_center) = (
+                        mirror_y(Vec3::Y * offset_y).y,
+                        mirror_y(anchor_offset),
+                        mirror_y(infinite_offset),
+                        mirror_y(mirror_center.extend(0.0)).truncate(),
+                    );
+
+                    let layer_tilesets = used_tilesets(&tile_layer);
+                    let Some((layer_origin, layer_size)) =
+                        tile_layer_bounds(&tile_layer, &map_size)
+                            .filter(|_| !layer_tilesets.is_empty())
+                    else {
+                        log::info!(
+                            "Skipping layer {} because it does not contain any tiles.",
+                            layer.id()
+                        );
+                        commands.entity(layer_entity).insert(SkippedLayer::Empty);
+                        layer_storage
+                            .storage
+                            .insert(layer_index as u32, layer_entity);
+                        continue;
+                    };
+
+                    // The first tilemap is the layer entity and the others are its children.
+                    let mut tilesets: Vec<_> = layer_tilesets
+                        .into_iter()
+                        .filter(|index| tiled_map.tilemap_textures.contains_key(index))
+                        .collect();
+                    tilesets.sort();
+                    if tilesets.is_empty() {
+                        log::info!(
+                            "Skipping layer {} because its tilesets have no textures.",
+                            layer.id()
+                        );
+                        commands
+                            .entity(layer_entity)
+                            .insert(SkippedLayer::MissingTextures);
+                        layer_storage
+                            .storage
+                            .insert(layer_index as u32, layer_entity);
+                        continue;
+                    }
+                    let mut tilemaps: Vec<_> = tilesets
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, tileset_index)| LayerTilemap {
+                            entity: if i == 0 {
+                                layer_entity
+                            } else {
+                                let tileset = &tiled_map.map.tilesets()[tileset_index];
+                                commands
+                                    .spawn(Name::new(format!("Tiles-{}", tileset.name)))
+                                    .set_parent(layer_entity)
+                                    .id()
+                            },
+                            tileset_index,
+                            tiles: Vec::new(),
+                            plain_tiles: Vec::new(),
+                            extras: Vec::new(),
+                        })
+                        .collect();
+                    let plain_tiles = has_layer_flag(&layer, PLAIN_TILES_PROPERTY);
+                    let flip = if flip.any() && map_type != TilemapType::Square {
+                        log::warn!(
+                            "Layer {} is not flipped, only tile layers of orthogonal maps can be flipped.",
+                            layer.id()
+                        );
+                        BVec2::FALSE
+                    } else {
+                        flip
+                    };
+                    let mut warned_diagonal_flip = false;
+                    let staggered_layout =
+                        matches!(tiled_map.map.orientation, tiled::Orientation::Staggered).then(
+                            || StaggeredLayout::new(&tiled_map.map, layer_origin, layer_size, true),
+                        );
+
+                    for x in 0..layer_size.x {
+                        for y in 0..layer_size.y {
+                            // Transform TMX coords into bevy coords, the Y axis of Tiled
+                            // points down.
+                            let mapped_y = layer_size.y - 1 - y;
+
+                            let mapped_x = layer_origin.x + x as i32;
+                            let mapped_y = layer_origin.y + mapped_y as i32;
+
+                            let layer_tile = match tile_layer.get_tile(mapped_x, mapped_y) {
+                                Some(t) => t,
+                                None => {
+                                    continue;
+                                }
+                            };
+                            // Tiles of tilesets without textures are skipped.
+                            let Some(tilemap) = tilemaps.iter_mut().find(|tilemap| {
+                                tilemap.tileset_index == layer_tile.tileset_index()
+                            }) else {
+                                continue;
+                            };
+                            let tileset_index = tilemap.tileset_index;
+                            let tileset = &tiled_map.map.tilesets()[tileset_index];
+                            let tilemap_texture = &tiled_map.tilemap_textures[&tileset_index];
+
+                            let texture_index = match tilemap_texture {
+                                    TilemapTexture::Single(_) => layer_tile.id(),
+                                    #[cfg(not(feature = "atlas"))]
+                                    TilemapTexture::Vector(_) =>
+                                        *tiled_map.tile_image_offsets.get(&(tileset_index, layer_tile.id()))
+                                        .expect("The offset into to image vector should have been saved during the initial load."),
+                                    #[cfg(not(feature = "atlas"))]
+                                    _ => unreachable!()
+                                };
+
+                            // The quads of tiles keep their size, so rotating non-square
+                            // tiles stretches them instead of swapping their width and height
+                            // like Tiled does.
+                            if layer_tile.flip_d
+                                && tileset.tile_width != tileset.tile_height
+                                && !warned_diagonal_flip
+                            {
+                                warned_diagonal_flip = true;
+                                log::warn!(
+                                        "Layer {} has rotated tiles from tileset {}, they are stretched because the tiles are not square",
+                                        layer.name,
+                                        tileset.name
+                                    );
+                            }
+
+                            let tile_pos = match &staggered_layout {
+                                Some(layout) => layout.tile_pos(
+                                    &tiled_map.map,
+                                    IVec2::new(mapped_x, mapped_y),
+                                    true,
+                                ),
+                                None => TilePos {
+                                    x: if flip.x { layer_size.x - 1 - x } else { x },
+                                    y: if flip.y { layer_size.y - 1 - y } else { y },
+                                },
+                            };
+                            let tile_bundle = TileBundle {
+                                position: tile_pos,
+                                tilemap_id: TilemapId(tilemap.entity),
+                                texture_index: TileTextureIndex(texture_index),
+                                // Like in Tiled, the anti-diagonal flip is applied before the
+                                // others, so all rotations of square tiles match the editor.
+                                flip: TileFlip {
+                                    x: layer_tile.flip_h ^ flip.x,
+                                    y: layer_tile.flip_v ^ flip.y,
+                                    d: layer_tile.flip_d,
+                                },
+                                color: TileColor(layer_color.into()),
+                                ..Default::default()
+                            };
+                            let tile_key = (tileset_index, layer_tile.id());
+                            let animation = tile_animations.entry(tile_key).or_insert_with(|| {
+                                let tile = layer_tile.get_tile()?;
+                                tile_animation(tiled_map, tileset_index, tile.animation.as_deref()?)
+                            });
+                            if plain_tiles {
+                                // Animations are still needed to render the tiles.
+                                if let Some(animation) = animation {
+                                    tilemap.extras.push((
+                                        tilemap.plain_tiles.len(),
+                                        TileExtras {
+                                            animation: Some(*animation),
+                                            ..Default::default()
+                                        },
+                                    ));
+                                }
+                                tilemap.plain_tiles.push((tile_pos, tile_bundle));
+                                continue;
+                            }
+                            let tile_bundle = (
+                                tile_bundle,
+                                TileSource {
+                                    tileset_index,
+                                    tile_id: layer_tile.id(),
+                                },
+                            );
+                            // The index of the layer keeps names unique within the map.
+                            #[cfg(feature = "names")]
+                            let tile_bundle = (
+                                tile_bundle,
+                                Name::new(format!("tile-{}-{}x{}", layer_index, x, y)),
+                            );
+
+                            // Tiles are spawned later, so errors are logged with the layer.
+                            let properties = tile_properties.entry(tile_key).or_insert_with(|| {
+                                layer_tile
+                                    .get_tile()
+                                    .map(|tile| {
+                                        parse_properties(
+                                            &tile.properties,
+                                            layer_entity,
+                                            map_dir,
+                                            &type_registry,
+                                        )
+                                    })
+                                    .unwrap_or_default()
+                            });
+                            let colliders = if tiled_map.settings.generate_colliders {
+                                tile_colliders
+                                    .entry(tile_key)
+                                    .or_insert_with(|| {
+                                        shapes::TileColliders::from_tile(
+                                            &layer_tile.get_tile()?,
+                                            true,
+                                        )
+                                    })
+                                    .clone()
+                            } else {
+                                None
+                            };
+                            let class = tile_classes.entry(tile_key).or_insert_with(|| {
+                                let tile = layer_tile.get_tile()?;
+                                class_registry.0.get(tile.user_type.as_ref()?).copied()
+                            });
+                            let wang_id = if tiled_map.settings.wang_ids {
+                                *tile_wang_ids.entry(tile_key).or_insert_with(|| {
+                                    tileset.wang_sets.iter().find_map(|wang_set| {
+                                        let wang_tile =
+                                            wang_set.wang_tiles.get(&layer_tile.id())?;
+                                        Some(TileWangId(wang_tile.wang_id.0))
+                                    })
+                                })
+                            } else {
+                                None
+                            };
+                            if !properties.is_empty()
+                                || animation.is_some()
+                                || colliders.is_some()
+                                || class.is_some()
+                                || wang_id.is_some()
+                            {
+                                tilemap.extras.push((
+                                    tilemap.tiles.len(),
+                                    TileExtras {
+                                        properties: properties.clone(),
+                                        animation: *animation,
+                                        colliders,
+                                        colliders_as_children: tiled_map
+                                            .settings
+                                            .colliders_as_children,
+                                        class: *class,
+                                        wang_id,
+                                    },
+                                ));
+                            }
+                            tilemap.tiles.push((tile_pos, tile_bundle));
+                        }
+                    }
+
+                    // Infinite layers only cover the populated chunks, so they are shifted
+                    // to keep their tiles where they would be on a finite map.
+                    let chunk_origin = layer_origin - map_origin;
+                    let chunk_offset = Vec3::new(
+                        chunk_origin.x as f32 * grid_size.x,
+                        (map_size.y as i32 - chunk_origin.y - layer_size.y as i32) as f32
+                            * grid_size.y,
+                        0.0,
+                    );
+                    // Chunks of infinite layers can start on a row of a different parity.
+                    let first_tile = layer_origin + IVec2::new(0, layer_size.y as i32 - 1);
+                    let map_type = tilemap_type(&tiled_map.map, first_tile, true);
+                    // Staggered layouts already place the tiles of infinite layers.
+                    let (tilemap_size, tilemap_center) = match &staggered_layout {
+                        Some(layout) => (
+                            layout.size,
+                            staggered_center_transform(
+                                &tiled_map.map,
+                                layout.origin_in_pixels(&tiled_map.map, true),
+                                layer_index as f32 + z_offset,
+                                true,
+                            ) * Transform::from_translation(
+                                Vec3::new(offset_x, offset_y, 0.0) + infinite_offset,
+                            ),
+                        ),
+                        None => (
+                            layer_size,
+                            get_tilemap_center_transform(
+                                &map_size,
+                                &grid_size,
+                                &map_type,
+                                layer_index as f32 + z_offset,
+                            ) * Transform::from_translation(
+                                Vec3::new(offset_x, offset_y, 0.0) + chunk_offset,
+                            ),
+                        ),
+                    };
+                    let tilemap_transform = tilemap_mirror
+                        * mirror_tilemap_transform(
+                            Transform::from_translation(anchor_offset) * tilemap_center,
+                            tilemap_size,
+                            grid_size,
+                            flip,
+                            mirror_center,
+                        );
+                    let material = layer_material(&layer, &material_registry);
+                    for (i, tilemap) in tilemaps.into_iter().enumerate() {
+                        let tileset = &tiled_map.map.tilesets()[tilemap.tileset_index];
+                        // The other tilemaps are placed by the first one, their parent.
+                        let (transform, visibility) = if i == 0 {
+                            (tilemap_transform, visibility)
+                        } else {
+                            (Transform::IDENTITY, Visibility::Inherited)
+                        };
+                        commands.entity(tilemap.entity).insert(TilemapBundle {
+                            grid_size,
+                            size: tilemap_size,
+                            storage: TileStorage::empty(tilemap_size),
+                            texture: tiled_map.tilemap_textures[&tilemap.tileset_index].clone(),
+                            tile_size: TilemapTileSize {
+                                x: tileset.tile_width as f32,
+                                y: tileset.tile_height as f32,
+                            },
+                            spacing: TilemapSpacing {
+                                x: tileset.spacing as f32,
+                                y: tileset.spacing as f32,
+                            },
+                            transform,
+                            render_settings: layer_render_settings(
+                                &layer,
+                                &map_type,
+                                tiled_map.render_order,
+                                tilemap_size,
+                                tileset.tile_height as f32 > grid_size.y,
+                            ),
+                            map_type,
+                            visibility,
+                            ..Default::default()
+                        });
+                        if let Some(insert_material) = material {
+                            let mut tilemap_entity = commands.entity(tilemap.entity);
+                            tilemap_entity.remove::<Handle<StandardTilemapMaterial>>();
+                            insert_material(&mut tilemap_entity);
+                        }
+                        #[cfg(feature = "diagnostics")]
+                        {
+                            spawn_counts.tiles += tilemap.tiles.len() + tilemap.plain_tiles.len();
+                        }
+                        if plain_tiles {
+                            spawn_tiles(
+                                tilemap.entity,
+                                tilemap.plain_tiles,
+                                tilemap.extras,
+                                &mut commands,
+                            );
+                        } else {
+                            spawn_tiles(
+                                tilemap.entity,
+                                tilemap.tiles,
+                                tilemap.extras,
+                                &mut commands,
+                            );
+                        }
+                    }
+
+                    layer_storage
+                        .storage
+                        .insert(layer_index as u32, layer_entity);
+                }
+
+                for (e, properties) in object_references {
+                    let inserted = add_object_references(
+                        &properties,
+                        e,
+                        &object_entities,
+                        &type_registry,
+                        &mut commands,
+                    );
+                    if e == map_entity {
+                        map_components.extend(inserted);
+                    }
+                }
+                remove_stale_components(map_entity, map_components, &mut commands);
+                // Inserted after the commands spawning the tiles, which are applied before it.
+                commands.entity(map_entity).insert(MapSpawned);
+
+                spawned_events.send(TiledMapSpawned {
+                    map_entity,
+                    handle: map_handle.clone(),
+                });
+                #[cfg(feature = "diagnostics")]
+                {
+                    spawn_counts.maps += 1;
+                }
+            }
+        }
+    }
+    #[cfg(feature = "diagnostics")]
+    spawn_counts.measure(&mut diagnostics, start.elapsed());
+}
+
+/// Tiles of a layer from one of its tilesets, spawned in their own tilemap.
+struct LayerTilemap<B> {
+    entity: Entity,
+    tileset_index: usize,
+    tiles: Vec<(TilePos, B)>,
+    /// Tiles of layers without tile entities.
+    plain_tiles: Vec<(TilePos, TileBundle)>,
+    /// Indexed by the position of the tile in `tiles` or `plain_tiles`.
+    extras: Vec<(usize, TileExtras)>,
+}
+
+/// Components added to a tile on top of its [`TileBundle`].
+#[derive(Default)]
+struct TileExtras {
+    properties: Vec<ParsedProperty>,
+    animation: Option<AnimatedTile>,
+    colliders: Option<shapes::TileColliders>,
+    colliders_as_children: bool,
+    class: Option<InsertTiledClass>,
+    wang_id: Option<TileWangId>,
+}
+
+/// Spawns the tiles of a layer in a single batch, as children of the layer, and stores them in
+/// its [`TileStorage`].
+///
+/// `extras` are indexed by the position of the tile in `tiles`.
+fn spawn_tiles<B: Bundle>(
+    layer_entity: Entity,
+    tiles: Vec<(TilePos, B)>,
+    extras: Vec<(usize, TileExtras)>,
+    commands: &mut Commands,
+) {
+    commands.add(move |world: &mut World| {
+        let (positions, bundles): (Vec<_>, Vec<_>) = tiles.into_iter().unzip();
+        let tile_entities: Vec<Entity> = world.spawn_batch(bundles).collect();
+        let Some(mut layer) = world.get_entity_mut(layer_entity) else {
+            return;
+        };
+        layer.push_children(&tile_entities);
+        if let Some(mut tile_storage) = layer.get_mut::<TileStorage>() {
+            for (tile_pos, tile_entity) in positions.iter().zip(&tile_entities) {
+                tile_storage.set(tile_pos, *tile_entity);
+            }
+        }
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, world);
+        for (index, extras) in extras {
+            let tile_entity = tile_entities[index];
+            for property in extras.properties.iter() {
+                property.apply(tile_entity, &mut commands);
+            }
+            if let Some(animation) = extras.animation {
+                commands.entity(tile_entity).insert(animation);
+            }
+            if let Some(colliders) = extras.colliders {
+                commands.entity(tile_entity).insert(colliders);
+                if extras.colliders_as_children {
+                    commands
+                        .entity(tile_entity)
+                        .insert(shapes::TileColliderChildren);
+                }
+            }
+            if let Some(wang_id) = extras.wang_id {
+                commands.entity(tile_entity).insert(wang_id);
+            }
+            if let Some(insert_class) = extras.class {
+                insert_class(&mut commands.entity(tile_entity));
+            }
+        }
+        queue.apply(world);
+    });
+}
+
+/// Objects are placed between this Z and the Z of their layer.
+const OBJECT_Z_RANGE: f32 = 0.5;
+
+/// Returns the Z of an object within its layer, following the [`ObjectDrawOrder::TopDown`] draw
+/// order of Tiled, so objects lower on the screen are drawn in front of the others.
+fn object_z(obj: &tiled::Object, map: &tiled::Map) -> f32 {
+    let screen_y = match map.orientation {
+        // Isometric positions are measured along the axes of the map.
+        tiled::Orientation::Isometric => (obj.x + obj.y) / 2.0,
+        _ => obj.y,
+    };
+    let height = map_pixel_size(map).y.max(1.0);
+    (screen_y / height).clamp(0.0, 1.0) * OBJECT_Z_RANGE
+}
+
+/// Rotation of an object around its position, clockwise in degrees in Tiled.
+///
+/// Maps keeping the Y axis of Tiled are mirrored, which turns it counterclockwise.
+fn object_rotation(obj: &tiled::Object, flip_y: bool) -> Quat {
+    let angle = obj.rotation.to_radians();
+    Quat::from_rotation_z(if flip_y { -angle } else { angle })
+}
+
+/// Converts the position of an object on an isometric map to the space of its layer.
+///
+/// Tiled stores these positions along the axes of the map, measured in tile heights.
+fn isometric_object_position(
+    position: Vec2,
+    map: &tiled::Map,
+    grid_size: &TilemapGridSize,
+    flip_y: bool,
+) -> Vec2 {
+    // Shifted so tile centers have whole coordinates, like tile positions.
+    let tile_position = position / map.tile_height as f32 - 0.5;
+    let y = if flip_y {
+        map.height as f32 - 1.0 - tile_position.y
+    } else {
+        tile_position.y
+    };
+    helpers::square_grid::diamond::DiamondPos::project(Vec2::new(tile_position.x, y), grid_size)
+}
+
+/// Converts a position in pixels from the top-left corner of the map, like the positions of
+/// objects on orthogonal, staggered and hexagonal maps, to the space of its layer.
+///
+/// Layers are placed like tilemaps, relative to the center of their tile at [`TilePos`] `(0, 0)`,
+/// which is the bottom-left tile of the map in Tiled unless its Y axis is kept.
+fn pixel_position_in_layer(position: Vec2, map: &tiled::Map, flip_y: bool) -> Vec2 {
+    let origin = layer_origin_in_pixels(map, flip_y);
+    if flip_y {
+        Vec2::new(position.x - origin.x, origin.y - position.y)
+    } else {
+        position - origin
+    }
+}
+
+/// Position in pixels from the top-left corner of the map of the center of the tile at
+/// [`TilePos`] `(0, 0)` of layers covering the whole map.
+fn layer_origin_in_pixels(map: &tiled::Map, flip_y: bool) -> Vec2 {
+    let first_tile = IVec2::new(0, if flip_y { map.height as i32 - 1 } else { 0 });
+    tile_center_in_pixels(map, first_tile)
+}
+
+/// Center of a tile in pixels from the top-left corner of the map, as shown in Tiled.
+fn tile_center_in_pixels(map: &tiled::Map, tile: IVec2) -> Vec2 {
+    let tile_size = Vec2::new(map.tile_width as f32, map.tile_height as f32);
+    let position = tile.as_vec2();
+    match map.orientation {
+        tiled::Orientation::Orthogonal => (position + 0.5) * tile_size,
+        tiled::Orientation::Isometric => Vec2::new(
+            (position.x - position.y + map.height as f32) * tile_size.x / 2.0,
+            (position.x + position.y + 1.0) * tile_size.y / 2.0,
+        ),
+        tiled::Orientation::Staggered | tiled::Orientation::Hexagonal => {
+            let side_length = match map.orientation {
+                tiled::Orientation::Hexagonal => map.hex_side_length.unwrap_or(0) as f32,
+                _ => 0.0,
+            };
+            let odd = matches!(map.stagger_index, tiled::StaggerIndex::Odd);
+            // Staggered columns are shifted down and staggered rows to the right.
+            match map.stagger_axis {
+                tiled::StaggerAxis::X => {
+                    let shift = if (tile.x.rem_euclid(2) == 1) == odd {
+                        tile_size.y / 2.0
+                    } else {
+                        0.0
+                    };
+                    Vec2::new(
+                        position.x * (tile_size.x + side_length) / 2.0 + tile_size.x / 2.0,
+                        (position.y + 0.5) * tile_size.y + shift,
+                    )
+                }
+                tiled::StaggerAxis::Y => {
+                    let shift = if (tile.y.rem_euclid(2) == 1) == odd {
+                        tile_size.x / 2.0
+                    } else {
+                        0.0
+                    };
+                    Vec2::new(
+                        (position.x + 0.5) * tile_size.x + shift,
+                        position.y * (tile_size.y + side_length) / 2.0 + tile_size.y / 2.0,
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Placement of the tiles of a layer of a staggered map.
+///
+/// `bevy_ecs_tilemap` places staggered tiles on a skewed grid, where every row is shifted by half
+/// of a tile from the previous one, while Tiled shifts every other row or column back and forth.
+/// Tiles are moved to the tile positions at their place in Tiled, so the tilemap is larger than
+/// the layer and has empty tiles around it.
+struct StaggeredLayout {
+    /// Added to the grid positions of the tiles so they all have positive tile positions.
+    offset: IVec2,
+    size: TilemapSize,
+}
+
+impl StaggeredLayout {
+    /// Layout of the tiles of a layer starting at `origin`, in tiles of the map.
+    fn new(map: &tiled::Map, origin: IVec2, size: TilemapSize, flip_y: bool) -> Self {
+        let positions = (0..size.x as i32).flat_map(|x| {
+            (0..size.y as i32)
+                .map(move |y| staggered_grid_pos(map, origin + IVec2::new(x, y), flip_y))
+        });
+        let (min, max) = positions.fold((Vec2::MAX, Vec2::MIN), |(min, max), position| {
+            (min.min(position), max.max(position))
+        });
+        // Shifting the rows by an odd number moves the tiles by half of a tile, which keeps them
+        // on whole tile positions when they are between them.
+        let mut offset_y = -min.y as i32;
+        if (min.x - offset_y as f32 / 2.0).fract() != 0.0 {
+            offset_y += 1;
+        }
+        let offset = IVec2::new(-(min.x - offset_y as f32 / 2.0).floor() as i32, offset_y);
+        let last = Self::shifted(max, offset);
+        Self {
+            offset,
+            size: TilemapSize {
+                x: last.x as u32 + 1,
+                y: last.y as u32 + 1,
+            },
+        }
+    }
+
+    /// Shifts a grid position by `offset`, moving the rows along the skewed grid.
+    fn shifted(position: Vec2, offset: IVec2) -> IVec2 {
+        IVec2::new(
+            (position.x - offset.y as f32 / 2.0).round() as i32 + offset.x,
+            position.y as i32 + offset.y,
+        )
+    }
+
+    /// Position in the tilemap of a tile of the map.
+    fn tile_pos(&self, map: &tiled::Map, tile: IVec2, flip_y: bool) -> TilePos {
+        let position = Self::shifted(staggered_grid_pos(map, tile, flip_y), self.offset);
+        TilePos {
+            x: position.x as u32,
+            y: position.y as u32,
+        }
+    }
+
+    /// Position in pixels from the top-left corner of the map of the center of the tile at
+    /// [`TilePos`] `(0, 0)`, which is usually outside of the map.
+    fn origin_in_pixels(&self, map: &tiled::Map, flip_y: bool) -> Vec2 {
+        let half_height = self.offset.y as f32 * map.tile_height as f32 / 2.0;
+        Vec2::new(
+            -self.offset.x as f32 * map.tile_width as f32,
+            if flip_y { half_height } else { -half_height },
+        )
+    }
+}
+
+/// Position of a tile of a staggered map on the skewed grid of `bevy_ecs_tilemap`, before it is
+/// shifted to positive tile positions. Rows are half of a tile height apart, so the X position
+/// is halfway between two tile positions on every other row.
+fn staggered_grid_pos(map: &tiled::Map, tile: IVec2, flip_y: bool) -> Vec2 {
+    let center = tile_center_in_pixels(map, tile);
+    let y = 2.0 * center.y / map.tile_height as f32;
+    let y = if flip_y { -y } else { y };
+    Vec2::new(center.x / map.tile_width as f32 - y / 2.0, y)
+}
+
+/// Transform placing the center of a layer of a staggered map at the center of the map.
+///
+/// `origin` is the position in pixels from the top-left corner of the map of the origin of the
+/// layer. The skewed tilemaps of `bevy_ecs_tilemap` cannot be centered from their size, so the
+/// size of the map in Tiled is used.
+fn staggered_center_transform(map: &tiled::Map, origin: Vec2, z: f32, flip_y: bool) -> Transform {
+    let offset = origin - map_pixel_size(map) / 2.0;
+    Transform::from_xyz(offset.x, if flip_y { -offset.y } else { offset.y }, z)
+}
+
+/// Size of the map in pixels, as shown in Tiled.
+fn map_pixel_size(map: &tiled::Map) -> Vec2 {
+    let (width, height) = (map.width as f32, map.height as f32);
+    let tile_size = Vec2::new(map.tile_width as f32, map.tile_height as f32);
+    match map.orientation {
+        tiled::Orientation::Orthogonal => Vec2::new(width, height) * tile_size,
+        tiled::Orientation::Isometric => (width + height) * tile_size / 2.0,
+        tiled::Orientation::Staggered | tiled::Orientation::Hexagonal => {
+            let side_length = match map.orientation {
+                tiled::Orientation::Hexagonal => map.hex_side_length.unwrap_or(0) as f32,
+                _ => 0.0,
+            };
+            // Every other row or column is shifted by half of a tile.
+            match map.stagger_axis {
+                tiled::StaggerAxis::X => Vec2::new(
+                    width * (tile_size.x + side_length) / 2.0 + (tile_size.x - side_length) / 2.0,
+                    height * tile_size.y + tile_size.y / 2.0,
+                ),
+                tiled::StaggerAxis::Y => Vec2::new(
+                    width * tile_size.x + tile_size.x / 2.0,
+                    height * (tile_size.y + side_length) / 2.0 + (tile_size.y - side_length) / 2.0,
+                ),
+            }
+        }
+    }
+}
+
+/// Picks the type of the tilemap for the orientation of the map.
+///
+/// `first_tile` is the position in Tiled of the tile at [`TilePos`] `(0, 0)` of the tilemap.
+fn tilemap_type(map: &tiled::Map, first_tile: IVec2, flip_y: bool) -> TilemapType {
+    match map.orientation {
+        tiled::Orientation::Hexagonal => TilemapType::Hexagon(hex_coord_system(
+            map.stagger_axis,
+            map.stagger_index,
+            first_tile,
+            flip_y,
+        )),
+        tiled::Orientation::Isometric => TilemapType::Isometric(IsoCoordSystem::Diamond),
+        tiled::Orientation::Staggered => TilemapType::Isometric(IsoCoordSystem::Staggered),
+        tiled::Orientation::Orthogonal => TilemapType::Square,
+    }
+}
+
+/// Picks the hex coordinate system matching the stagger settings of a hexagonal map.
+///
+/// Staggering along the X axis shifts columns, staggering along the Y axis shifts rows.
+/// Tiled counts the staggered rows and columns from its first tile, while the tilemap counts them
+/// from `first_tile`, its tile at [`TilePos`] `(0, 0)`.
+fn hex_coord_system(
+    stagger_axis: tiled::StaggerAxis,
+    stagger_index: tiled::StaggerIndex,
+    first_tile: IVec2,
+    flip_y: bool,
+) -> HexCoordSystem {
+    let odd = matches!(stagger_index, tiled::StaggerIndex::Odd);
+    match stagger_axis {
+        // Tiled shifts staggered columns down, the odd columns of `ColumnOdd` are shifted up.
+        // When the Y axis of Tiled is kept, down in Tiled is up in Bevy.
+        tiled::StaggerAxis::X => {
+            if odd ^ (first_tile.x & 1 == 1) ^ flip_y {
+                HexCoordSystem::ColumnOdd
+            } else {
+                HexCoordSystem::ColumnEven
+            }
+        }
+        // Both Tiled and `RowOdd` shift staggered rows to the right.
+        tiled::StaggerAxis::Y => {
+            if odd ^ (first_tile.y & 1 == 1) {
+                HexCoordSystem::RowOdd
+            } else {
+                HexCoordSystem::RowEven
+            }
+        }
+    }
+}
+
+/// Size of the grid of the tilemap.
+///
+/// Hexagonal tilemaps place staggered rows or columns 3/4 of the grid size apart, while Tiled
+/// places them by half of the tile size plus half of the side length of the hexagons.
+fn map_grid_size(map: &tiled::Map) -> TilemapGridSize {
+    let mut grid_size = TilemapGridSize {
+        x: map.tile_width as f32,
+        y: map.tile_height as f32,
+    };
+    if let (tiled::Orientation::Hexagonal, Some(side_length)) =
+        (map.orientation, map.hex_side_length)
+    {
+        let side_length = side_length as f32;
+        match map.stagger_axis {
+            tiled::StaggerAxis::X => grid_size.x = (grid_size.x + side_length) * 2.0 / 3.0,
+            tiled::StaggerAxis::Y => grid_size.y = (grid_size.y + side_length) * 2.0 / 3.0,
+        }
+    }
+    grid_size
+}
+
+/// Collects the layers of the map in depth-first order, along with the index of the group
+/// layer that contains them.
+fn flatten_layers<'map>(
+    layers: impl Iterator<Item = tiled::Layer<'map>>,
+    parent_group: Option<usize>,
+    out: &mut Vec<(tiled::Layer<'map>, Option<usize>)>,
+) {
+    for layer in layers {
+        let index = out.len();
+        out.push((layer, parent_group));
+        if let tiled::LayerType::Group(group) = layer.layer_type() {
+            flatten_layers(group.layers(), Some(index), out);
+        }
+    }
+}
+
+/// Returns the shape of an object, relative to its position.
+///
+/// Tile objects are placed by the `tile_anchor` of their sprite, their bottom-left corner like in
+/// Tiled by default, so their rectangle is centered on their sprite.
+fn object_shape(
+    obj: &tiled::Object,
+    tile_anchor: Option<TileObjectAnchor>,
+    flip_y: bool,
+) -> shapes::TiledObjectShape {
+    let mut shape = shapes::TiledObjectShape::from(&obj.shape);
+    if let (Some(anchor), Some(size)) =
+        (tile_anchor, shapes::TiledObjectSize::from_shape(&obj.shape))
+    {
+        shape = shape.centered_at(anchor.box_center(Vec2::new(size.width, size.height)));
+    }
+    if flip_y {
+        shape
+    } else {
+        shape.mirrored_y()
+    }
+}
+
+/// Creates the sprite rendering the tile of a tile object.
+///
+/// Tiles of objects instantiated from templates come from the tileset of the template, which is
+/// found in the map by its file.
+///
+/// Returns `None` if the object does not reference a tile or the tileset of its template is not
+/// used by the map.
+fn tile_object_sprite(
+    tiled_map: &TiledMap,
+    obj: &tiled::Object,
+) -> Option<(Sprite, Handle<Image>)> {
+    let tile = obj.get_tile()?;
+    let tileset_index = match tile.tileset_location() {
+        tiled::TilesetLocation::Map(tileset_index) => *tileset_index,
+        tiled::TilesetLocation::Template(tileset) => {
+            let source = normalize_path(&tileset.source);
+            let Some(tileset_index) = tiled_map
+                .map
+                .tilesets()
+                .iter()
+                .position(|map_tileset| normalize_path(&map_tileset.source) == source)
+            else {
+                log::warn!(
+                    "Skipping sprite of object {} with a tile from the tileset {:?} of a template, which is not used by the map.",
+                    obj.id(),
+                    tileset.source
+                );
+                return None;
+            };
+            tileset_index
+        }
+    };
+    let tileset = tile.get_tileset();
+    let (texture, rect) = match tiled_map.tilemap_textures.get(&tileset_index)? {
+        TilemapTexture::Single(texture) => {
+            let columns = tileset.columns.max(1);
+            let (column, row) = (tile.id() % columns, tile.id() / columns);
+            let min = Vec2::new(
+                (tileset.margin + column * (tileset.tile_width + tileset.spacing)) as f32,
+                (tileset.margin + row * (tileset.tile_height + tileset.spacing)) as f32,
+            );
+            let size = Vec2::new(tileset.tile_width as f32, tileset.tile_height as f32);
+            (texture.clone(), Some(Rect::from_corners(min, min + size)))
+        }
+        #[cfg(not(feature = "atlas"))]
+        TilemapTexture::Vector(textures) => {
+            let offset = tiled_map
+                .tile_image_offsets
+                .get(&(tileset_index, tile.id()))?;
+            (textures.get(*offset as usize)?.clone(), None)
+        }
+        #[cfg(not(feature = "atlas"))]
+        _ => return None,
+    };
+    let custom_size = match obj.shape {
+        tiled::ObjectShape::Rect { width, height } if width > 0.0 && height > 0.0 => {
+            Some(Vec2::new(width, height))
+        }
+        _ => None,
+    };
+    let sprite = Sprite {
+        flip_x: tile.flip_h,
+        flip_y: tile.flip_v,
+        custom_size,
+        rect,
+        ..Default::default()
+    };
+    Some((sprite, texture))
+}
+
+/// Returns the text of a text object, aligned in its box like in Tiled.
+///
+/// The font is loaded from the `font` property, relative to the map file, and falls back to the
+/// default font of Bevy.
+fn text_object_bundle(
+    obj: &tiled::Object,
+    map_dir: &Path,
+    asset_server: &AssetServer,
+    flip_y: bool,
+) -> Option<Text2dBundle> {
+    let tiled::ObjectShape::Text {
+        pixel_size,
+        wrap,
+        color,
+        halign,
+        valign,
+        text,
+        width,
+        height,
+        ..
+    } = &obj.shape
+    else {
+        return None;
+    };
+    let font = match obj.properties.get("font") {
+        Some(tiled::PropertyValue::FileValue(path) | tiled::PropertyValue::StringValue(path)) => {
+            asset_server.load(map_dir.join(path))
+        }
+        _ => Handle::default(),
+    };
+    let style = TextStyle {
+        font,
+        font_size: *pixel_size as f32,
+        color: Color::srgba_u8(color.red, color.green, color.blue, color.alpha),
+    };
+    let (x, justify) = match halign {
+        tiled::HorizontalAlignment::Left | tiled::HorizontalAlignment::Justify => {
+            (0.0, JustifyText::Left)
+        }
+        tiled::HorizontalAlignment::Center => (width / 2.0, JustifyText::Center),
+        tiled::HorizontalAlignment::Right => (*width, JustifyText::Right),
+    };
+    let y = match valign {
+        tiled::VerticalAlignment::Top => 0.0,
+        tiled::VerticalAlignment::Center => -height / 2.0,
+        tiled::VerticalAlignment::Bottom => -height,
+    };
+    let anchor = match (valign, justify) {
+        (tiled::VerticalAlignment::Top, JustifyText::Left) => bevy::sprite::Anchor::TopLeft,
+        (tiled::VerticalAlignment::Top, JustifyText::Center) => bevy::sprite::Anchor::TopCenter,
+        (tiled::VerticalAlignment::Top, _) => bevy::sprite::Anchor::TopRight,
+        (tiled::VerticalAlignment::Center, JustifyText::Left) => bevy::sprite::Anchor::CenterLeft,
+        (tiled::VerticalAlignment::Center, JustifyText::Center) => bevy::sprite::Anchor::Center,
+        (tiled::VerticalAlignment::Center, _) => bevy::sprite::Anchor::CenterRight,
+        (tiled::VerticalAlignment::Bottom, JustifyText::Left) => bevy::sprite::Anchor::BottomLeft,
+        (tiled::VerticalAlignment::Bottom, JustifyText::Center) => {
+            bevy::sprite::Anchor::BottomCenter
+        }
+        (tiled::VerticalAlignment::Bottom, _) => bevy::sprite::Anchor::BottomRight,
+    };
+    // Maps keeping the Y axis of Tiled are mirrored, so the text is mirrored back.
+    let transform = if flip_y {
+        Transform::from_xyz(x, y, 0.0)
+    } else {
+        Transform::from_xyz(x, -y, 0.0).with_scale(Vec3::new(1.0, -1.0, 1.0))
+    };
+    Some(Text2dBundle {
+        text: Text::from_section(text.clone(), style).with_justify(justify),
+        text_anchor: anchor,
+        text_2d_bounds: if *wrap {
+            bevy::text::Text2dBounds {
+                size: Vec2::new(*width, f32::INFINITY),
+            }
+        } else {
+            bevy::text::Text2dBounds::UNBOUNDED
+        },
+        transform,
+        ..Default::default()
+    })
+}
+
+/// Warns when the tile images of an image collection tileset differ in size.
+///
+/// `bevy_ecs_tilemap` stores the images in a single texture array, so they are all expected to
+/// have the same size.
+#[cfg(not(feature = "atlas"))]
+fn warn_mixed_image_sizes(tiled_map: &TiledMap, tileset_index: usize, tileset: &tiled::Tileset) {
+    let mut sizes: Vec<UVec2> = tiled_map
+        .tile_image_sizes
+        .iter()
+        .filter(|((index, _), _)| *index == tileset_index)
+        .map(|(_, size)| *size)
+        .collect();
+    sizes.sort_by_key(|size| (size.x, size.y));
+    sizes.dedup();
+    if sizes.len() > 1 {
+        log::warn!(
+            "Tileset {} has tile images of different sizes {:?}, they will not render correctly. \
+            All images of an image collection tileset have to be the same size.",
+            tileset.name,
+            sizes
+        );
+    }
+}
+
+/// Converts the animation of a tile into an [`AnimatedTile`].
+///
+/// The frames have to use consecutive texture indices, their durations are averaged.
+fn tile_animation(
+    tiled_map: &TiledMap,
+    tileset_index: usize,
+    frames: &[tiled::Frame],
+) -> Option<AnimatedTile> {
+    let texture_index = |tile_id| match tiled_map.tilemap_textures.get(&tileset_index)? {
+        TilemapTexture::Single(_) => Some(tile_id),
+        #[cfg(not(feature = "atlas"))]
+        TilemapTexture::Vector(_) => tiled_map
+            .tile_image_offsets
+            .get(&(tileset_index, tile_id))
+            .copied(),
+        #[cfg(not(feature = "atlas"))]
+        _ => None,
+    };
+    let indices = frames
+        .iter()
+        .map(|frame| texture_index(frame.tile_id))
+        .collect::<Option<Vec<u32>>>()?;
+    let start = *indices.first()?;
+    if (start..)
+        .zip(&indices)
+        .any(|(expected, index)| expected != *index)
+    {
+        log::warn!(
+            "Skipping animation of tile {} in tileset {}, its frames have to be consecutive tiles.",
+            frames[0].tile_id,
+            tileset_index
+        );
+        return None;
+    }
+    let duration: u32 = frames.iter().map(|frame| frame.duration).sum();
+    if duration == 0 {
+        return None;
+    }
+    Some(AnimatedTile {
+        start,
+        end: start + indices.len() as u32,
+        // A speed of 1 plays the whole animation once per second.
+        speed: 1000.0 / duration as f32,
+    })
+}
+
+/// Combines the tint color and opacity of the layer with the ones inherited from its group layers.
+fn layer_color(layer: &tiled::Layer, parent_color: Srgba) -> Srgba {
+    let tint = layer
+        .tint_color
+        .map(|c| Srgba::rgba_u8(c.red, c.green, c.blue, c.alpha))
+        .unwrap_or(Srgba::WHITE);
+    Srgba::new(
+        parent_color.red * tint.red,
+        parent_color.green * tint.green,
+        parent_color.blue * tint.blue,
+        parent_color.alpha * tint.alpha * layer.opacity,
+    )
+}
+
+/// Returns the indices of the tilesets of the tiles of a layer.
+fn used_tilesets(tile_layer: &tiled::TileLayer) -> HashSet<usize> {
+    match tile_layer {
+        tiled::TileLayer::Finite(layer) => (0..layer.width() as i32)
+            .flat_map(|x| (0..layer.height() as i32).filter_map(move |y| layer.get_tile(x, y)))
+            .map(|tile| tile.tileset_index())
+            .collect(),
+        tiled::TileLayer::Infinite(layer) => layer
+            .chunks()
+            .flat_map(|(_, chunk)| {
+                (0..tiled::ChunkData::WIDTH as i32).flat_map(move |x| {
+                    (0..tiled::ChunkData::HEIGHT as i32).filter_map(move |y| chunk.get_tile(x, y))
+                })
+            })
+            .map(|tile| tile.tileset_index())
+            .collect(),
+    }
+}
+
+/// Returns the position of the top-left tile (in Tiled coordinates) and the size of the area
+/// covered by a tile layer.
+///
+/// Finite layers always cover the whole map. Infinite layers cover the bounding box of their
+/// populated chunks, empty chunks are ignored. Returns `None` if an infinite layer has no tiles.
+fn tile_layer_bounds(
+    tile_layer: &tiled::TileLayer,
+    map_size: &TilemapSize,
+) -> Option<(IVec2, TilemapSize)> {
+    let tiled::TileLayer::Infinite(infinite_layer) = tile_layer else {
+        return Some((IVec2::ZERO, *map_size));
+    };
+    let chunk_size = IVec2::new(
+        tiled::ChunkData::WIDTH as i32,
+        tiled::ChunkData::HEIGHT as i32,
+    );
+    let (min, max) = infinite_layer
+        .chunks()
+        .filter(|(_, chunk)| {
+            (0..chunk_size.x).any(|x| (0..chunk_size.y).any(|y| chunk.get_tile(x, y).is_some()))
+        })
+        .map(|((x, y), _)| IVec2::new(x, y) * chunk_size)
+        .fold(None, |bounds: Option<(IVec2, IVec2)>, chunk_pos| {
+            let (min, max) = bounds.unwrap_or((chunk_pos, chunk_pos));
+            Some((min.min(chunk_pos), max.max(chunk_pos)))
+        })?;
+    let size = max + chunk_size - min;
+    Some((
+        min,
+        TilemapSize {
+            x: size.x as u32,
+            y: size.y as u32,
+        },
+    ))
+}
+
+/// Returns the position of the top-left tile (in Tiled coordinates) and the size of the area
+/// covered by the populated chunks of the tile layers of an infinite map.
+///
+/// Returns `None` for finite maps and infinite maps without tiles.
+fn infinite_map_bounds(map: &tiled::Map) -> Option<(IVec2, TilemapSize)> {
+    if !map.infinite() {
+        return None;
+    }
+    let mut layers = Vec::new();
+    flatten_layers(map.layers(), None, &mut layers);
+    let (min, max) = layers
+        .iter()
+        .filter_map(|(layer, _)| match layer.layer_type() {
+            tiled::LayerType::Tiles(tile_layer) => {
+                tile_layer_bounds(&tile_layer, &TilemapSize { x: 0, y: 0 })
+            }
+            _ => None,
+        })
+        .map(|(origin, size)| (origin, origin + IVec2::new(size.x as i32, size.y as i32)))
+        .reduce(|(min, max), (origin, end)| (min.min(origin), max.max(end)))?;
+    let size = max - min;
+    Some((
+        min,
+        TilemapSize {
+            x: size.x as u32,
+            y: size.y as u32,
+        },
+    ))
+}
+
+/// Offset of the contents of an infinite map starting at the tile `origin` instead of at the
+/// origin of Tiled like finite maps.
+///
+/// Chunks start at even positions, so the offset is the same for every tile of staggered maps.
+fn infinite_map_offset(map: &tiled::Map, origin: IVec2, flip_y: bool) -> Vec3 {
+    let offset = tile_center_in_pixels(map, IVec2::ZERO) - tile_center_in_pixels(map, origin);
+    Vec3::new(offset.x, if flip_y { -offset.y } else { offset.y }, 0.0)
+}
+
+const REMOVE_PREFIX: &str = "remove:";
+/// Handles a property matching no registered type, with the entity it was set on, the name of
+/// the property and its value.
+pub type HandleTiledProperty =
+    dyn Fn(&mut EntityCommands, &str, &tiled::PropertyValue) + Send + Sync;
+
+/// Handler of the properties of maps, layers, tilesets, objects and tiles whose name matches no
+/// registered type, like `event:open_door`. They are ignored without it.
+///
+/// Set with [`SetTiledPropertyHandler::set_tiled_property_handler`]. Properties read by this
+/// crate, like `z_offset`, are not passed to it.
+#[derive(Resource)]
+pub struct TiledPropertyHandler(pub Box<HandleTiledProperty>);
+
+/// Extension for [`App`] to handle properties matching no registered type.
+pub trait SetTiledPropertyHandler {
+    /// Calls `handler` for each property whose name matches no registered type, replacing the
+    /// previous handler.
+    fn set_tiled_property_handler(
+        &mut self,
+        handler: impl Fn(&mut EntityCommands, &str, &tiled::PropertyValue) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl SetTiledPropertyHandler for App {
+    fn set_tiled_property_handler(
+        &mut self,
+        handler: impl Fn(&mut EntityCommands, &str, &tiled::PropertyValue) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.insert_resource(TiledPropertyHandler(Box::new(handler)))
+    }
+}
+
+/// Prefix of properties setting a resource instead of a component, for types that are both.
+const RESOURCE_PREFIX: &str = "resource:";
+
+/// Float layer property added to the Z position of the layer, which is its index otherwise.
+const Z_OFFSET_PROPERTY: &str = "z_offset";
+
+/// Bool layer property spawning the tiles of the layer as plain tiles, with only the components
+/// needed to render them.
+///
+/// An entity is still spawned for every tile, `bevy_ecs_tilemap` only renders tiles which are
+/// entities, so this cuts the components of the tiles but not their number.
+const PLAIN_TILES_PROPERTY: &str = "plain_tiles";
+
+/// Bool layer property sorting the tiles of an isometric layer by their Y position on the screen,
+/// set to `false` to draw tiles taller than the grid in the default chunks.
+const Y_SORT_PROPERTY: &str = "y_sort";
+
+/// Color object property multiplied with the tint and opacity of the layer in the sprite of a tile
+/// object.
+const COLOR_PROPERTY: &str = "color";
+
+/// Color of the sprite of a tile object, from the color of its layer and its [`COLOR_PROPERTY`].
+fn tile_object_color(obj: &tiled::Object, layer_color: Srgba) -> Srgba {
+    let Some(tiled::PropertyValue::ColorValue(c)) = obj.properties.get(COLOR_PROPERTY) else {
+        return layer_color;
+    };
+    let color = Srgba::rgba_u8(c.red, c.green, c.blue, c.alpha);
+    Srgba::new(
+        layer_color.red * color.red,
+        layer_color.green * color.green,
+        layer_color.blue * color.blue,
+        layer_color.alpha * color.alpha,
+    )
+}
+
+/// Bool layer properties mirroring the layer horizontally and vertically around the center of the
+/// map, along with the layers of group layers.
+const FLIP_X_PROPERTY: &str = "flip_x";
+const FLIP_Y_PROPERTY: &str = "flip_y";
+
+/// String layer property naming the material of a tile layer, registered with
+/// [`RegisterTiledMaterial::register_tiled_material`].
+const MATERIAL_PROPERTY: &str = "material";
+
+/// Properties read by this crate, which are not passed to the [`TiledPropertyHandler`].
+const BUILTIN_PROPERTIES: [&str; 9] = [
+    "anchor",
+    "font",
+    Z_OFFSET_PROPERTY,
+    PLAIN_TILES_PROPERTY,
+    Y_SORT_PROPERTY,
+    COLOR_PROPERTY,
+    FLIP_X_PROPERTY,
+    FLIP_Y_PROPERTY,
+    MATERIAL_PROPERTY,
+];
+
+fn layer_material<'a>(
+    layer: &tiled::Layer,
+    registry: &'a TiledMaterialRegistry,
+) -> Option<&'a InsertTiledMaterial> {
+    let Some(tiled::PropertyValue::StringValue(name)) = layer.properties.get(MATERIAL_PROPERTY)
+    else {
+        return None;
+    };
+    let Some(insert_material) = registry.0.get(name) else {
+        log::warn!(
+            "Layer {} uses the material {} which was not registered",
+            layer.id(),
+            name
+        );
+        return None;
+    };
+    Some(insert_material.as_ref())
+}
+
+fn has_layer_flag(layer: &tiled::Layer, property: &str) -> bool {
+    matches!(
+        layer.properties.get(property),
+        Some(tiled::PropertyValue::BoolValue(true))
+    )
+}
+
+/// Mirrors a transform along the flipped axes, around `center`.
+fn mirror_transform(transform: Transform, flip: BVec2, center: Vec2) -> Transform {
+    if !flip.any() {
+        return transform;
+    }
+    let scale = Vec2::select(flip, Vec2::NEG_ONE, Vec2::ONE);
+    Transform::from_translation(center.extend(0.0))
+        * Transform::from_scale(scale.extend(1.0))
+        * Transform::from_translation(-center.extend(0.0))
+        * transform
+}
+
+/// Moves a square tilemap whose tiles were mirrored around the center of the tilemap, so that it
+/// is mirrored around `center` instead.
+fn mirror_tilemap_transform(
+    transform: Transform,
+    size: TilemapSize,
+    grid_size: TilemapGridSize,
+    flip: BVec2,
+    center: Vec2,
+) -> Transform {
+    if !flip.any() {
+        return transform;
+    }
+    let scale = Vec2::select(flip, Vec2::NEG_ONE, Vec2::ONE);
+    let tilemap_center = Vec2::new(
+        (size.x - 1) as f32 * grid_size.x,
+        (size.y - 1) as f32 * grid_size.y,
+    ) / 2.0;
+    let position = transform.translation.truncate();
+    let position = center - tilemap_center + scale * (position + tilemap_center - center);
+    transform.with_translation(position.extend(transform.translation.z))
+}
+
+/// Render settings of a tile layer, sorting every tile on its own on isometric layers with the
+/// [`Y_SORT_PROPERTY`].
+///
+/// Tiles of a tilemap are drawn together in chunks, so a chunk of a single tile is needed to draw
+/// objects and other layers between them. Each of them is then drawn separately, so it is only
+/// worth it on layers which need it.
+///
+/// Isometric tiles taller than the grid overlap the tiles above them, so their layers are drawn in
+/// a single chunk unless the property is set. Chunks draw their rows from [`TilePos`] `y` 0 up,
+/// which are the rows of Tiled from the bottom, so upper rows are drawn in front like with the
+/// `right-up` render order. Maps drawing lower rows in front need the property to follow their
+/// `render_order`. Rows are always drawn from left to right.
+fn layer_render_settings(
+    layer: &tiled::Layer,
+    map_type: &TilemapType,
+    render_order: RenderOrder,
+    tilemap_size: TilemapSize,
+    overlapping: bool,
+) -> TilemapRenderSettings {
+    let is_isometric = matches!(map_type, TilemapType::Isometric(_));
+    let sorted = TilemapRenderSettings {
+        render_chunk_size: UVec2::ONE,
+        y_sort: true,
+    };
+    match layer.properties.get(Y_SORT_PROPERTY) {
+        Some(tiled::PropertyValue::BoolValue(true)) if !is_isometric => {
+            log::warn!(
+                "Ignoring the {} property of layer {}, only isometric layers are sorted",
+                Y_SORT_PROPERTY,
+                layer.name
+            );
+            TilemapRenderSettings::default()
+        }
+        Some(tiled::PropertyValue::BoolValue(true)) => sorted,
+        Some(tiled::PropertyValue::BoolValue(false)) => TilemapRenderSettings::default(),
+        _ if !(is_isometric && overlapping) => TilemapRenderSettings::default(),
+        // A single chunk draws the rows from the bottom, sorting every tile is opt-in as it
+        // costs a draw call per tile.
+        _ => {
+            if render_order.is_down() {
+                log::info!(
+                    "Layer {} draws upper rows in front, set its {} property to follow the render order of the map",
+                    layer.name,
+                    Y_SORT_PROPERTY
+                );
+            }
+            TilemapRenderSettings {
+                render_chunk_size: UVec2::new(tilemap_size.x, tilemap_size.y),
+                y_sort: false,
+            }
+        }
+    }
+}
+
+fn layer_z_offset(layer: &tiled::Layer) -> f32 {
+    match layer.properties.get(Z_OFFSET_PROPERTY) {
+        Some(tiled::PropertyValue::FloatValue(z_offset)) => *z_offset,
+        Some(tiled::PropertyValue::IntValue(z_offset)) => *z_offset as f32,
+        _ => 0.0,
+    }
+}
+
+/// A property parsed into a component change, which can be applied to any number of entities.
+#[derive(Clone)]
+enum ParsedProperty {
+    Insert {
+        reflect_component: ReflectComponent,
+        component: Arc<dyn Reflect>,
+    },
+    /// Inserts or overwrites a resource, regardless of the entity it is applied to.
+    InsertResource {
+        reflect_resource: ReflectResource,
+        resource: Arc<dyn Reflect>,
+    },
+    /// Inserts a component wrapping the handle of a file, which is loaded when applied.
+    InsertFile {
+        reflect_component: ReflectComponent,
+        type_info: &'static TypeInfo,
+        loader: LoadFileAsset,
+        path: PathBuf,
+    },
+    Remove(&'static str),
+    /// Passed to the [`TiledPropertyHandler`], if any.
+    Unresolved {
+        name: String,
+        value: tiled::PropertyValue,
+    },
+}
+
+impl ParsedProperty {
+    /// Type of the inserted component, `None` for resources and removals.
+    fn inserted_type(&self) -> Option<TypeId> {
+        match self {
+            ParsedProperty::Insert { component, .. } => {
+                component.get_represented_type_info().map(TypeInfo::type_id)
+            }
+            ParsedProperty::InsertFile { type_info, .. } => Some(type_info.type_id()),
+            ParsedProperty::InsertResource { .. }
+            | ParsedProperty::Remove(_)
+            | ParsedProperty::Unresolved { .. } => None,
+        }
+    }
+
+    fn apply(&self, e: Entity, commands: &mut Commands) {
+        match self.clone() {
+            ParsedProperty::Insert {
+                reflect_component,
+                component,
+            } => commands.add(move |world: &mut World| {
+                let type_registry = world.resource::<AppTypeRegistry>().clone();
+                let type_registry = type_registry.read();
+                let Some(mut entity_mut) = world.get_entity_mut(e) else {
+                    return;
+                };
+                reflect_component.insert(&mut entity_mut, &*component, &type_registry);
+            }),
+            ParsedProperty::InsertResource {
+                reflect_resource,
+                resource,
+            } => commands.add(move |world: &mut World| {
+                let type_registry = world.resource::<AppTypeRegistry>().clone();
+                let type_registry = type_registry.read();
+                reflect_resource.insert(world, &*resource, &type_registry);
+            }),
+            ParsedProperty::InsertFile {
+                reflect_component,
+                type_info,
+                loader,
+                path,
+            } => {
+                insert_newtype_component(commands, e, type_info, reflect_component, move |world| {
+                    loader(world.resource::<AssetServer>(), path)
+                })
+            }
+            ParsedProperty::Remove(type_path) => {
+                commands.entity(e).remove_reflect(type_path);
+            }
+            ParsedProperty::Unresolved { name, value } => commands.add(move |world: &mut World| {
+                let Some(handler) = world.get_resource::<TiledPropertyHandler>() else {
+                    log::debug!("Ignored property {} on {:?}", name, e);
+                    return;
+                };
+                if world.get_entity(e).is_none() {
+                    return;
+                }
+                let mut queue = CommandQueue::default();
+                let mut commands = Commands::new(&mut queue, world);
+                (handler.0)(&mut commands.entity(e), &name, &value);
+                queue.apply(world);
+            }),
+        }
+    }
+}
+
+/// Removes the components inserted during the previous load of the map which are no longer
+/// in `inserted`, then stores `inserted` in [`TiledInsertedComponents`].
+fn remove_stale_components(map_entity: Entity, inserted: Vec<TypeId>, commands: &mut Commands) {
+    commands.add(move |world: &mut World| {
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+        let Some(mut entity_mut) = world.get_entity_mut(map_entity) else {
+            return;
+        };
+        let previous = entity_mut
+            .take::<TiledInsertedComponents>()
+            .unwrap_or_default();
+        for type_id in previous.0.iter().filter(|t| !inserted.contains(t)) {
+            if let Some(reflect_component) =
+                type_registry.get_type_data::<ReflectComponent>(*type_id)
+            {
+                reflect_component.remove(&mut entity_mut);
+            }
+        }
+        entity_mut.insert(TiledInsertedComponents(inserted));
+    });
+}
+
+type LoadFileAsset = fn(&AssetServer, PathBuf) -> Box<dyn Reflect>;
+
+/// Returns the function loading the asset of a file property into a handle of the given type.
+///
+/// Supported handle types are `Handle<Image>`, `Handle<Font>`, `Handle<Scene>` and
+/// `Handle<TiledMap>`.
+fn file_asset_loader(handle_type: TypeId) -> Option<LoadFileAsset> {
+    fn load<A: Asset>(asset_server: &AssetServer, path: PathBuf) -> Box<dyn Reflect> {
+        Box::new(asset_server.load::<A>(path))
+    }
+    [
+        (
+            TypeId::of::<Handle<Image>>(),
+            load::<Image> as LoadFileAsset,
+        ),
+        (TypeId::of::<Handle<Font>>(), load::<Font>),
+        (TypeId::of::<Handle<Scene>>(), load::<Scene>),
+        (TypeId::of::<Handle<TiledMap>>(), load::<TiledMap>),
+    ]
+    .into_iter()
+    .find_map(|(type_id, loader)| (type_id == handle_type).then_some(loader))
+}
+
+/// Returns the type of the only field of a tuple struct.
+fn newtype_field(type_info: &TypeInfo) -> Option<TypeId> {
+    match type_info {
+        TypeInfo::TupleStruct(info) if info.field_len() == 1 => {
+            info.field_at(0).map(|field| field.type_id())
+        }
+        _ => None,
+    }
+}
+
+/// Formats a color of Tiled, which is in sRGB, as a tuple struct with a single `Srgba`,
+/// `LinearRgba` or `Color` field. Other fields get a `Color`.
+fn color_ron(color: &tiled::Color, field_type: Option<TypeId>) -> String {
+    let srgba = Srgba::rgba_u8(color.red, color.green, color.blue, color.alpha);
+    if field_type == Some(TypeId::of::<LinearRgba>()) {
+        let linear = LinearRgba::from(srgba);
+        return format!(
+            "(LinearRgba(red:{},green:{},blue:{},alpha:{}))",
+            linear.red, linear.green, linear.blue, linear.alpha
+        );
+    }
+    let srgba = format!(
+        "Srgba(red:{},green:{},blue:{},alpha:{})",
+        srgba.red, srgba.green, srgba.blue, srgba.alpha
+    );
+    if field_type == Some(TypeId::of::<Srgba>()) {
+        format!("({})", srgba)
+    } else {
+        format!("(Srgba({}))", srgba)
+    }
+}
+
+/// Inserts a tuple struct component with a single field, which is created once the command runs.
+fn insert_newtype_component(
+    commands: &mut Commands,
+    e: Entity,
+    type_info: &'static TypeInfo,
+    reflect_component: ReflectComponent,
+    field: impl FnOnce(&World) -> Box<dyn Reflect> + Send + 'static,
+) {
+    commands.add(move |world: &mut World| {
+        let mut component = DynamicTupleStruct::default();
+        component.set_represented_type(Some(type_info));
+        component.insert_boxed(field(world));
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+        let Some(mut entity_mut) = world.get_entity_mut(e) else {
+            return;
+        };
+        reflect_component.insert(&mut entity_mut, &component, &type_registry);
+    });
+}
+
+/// Returns the object properties, which can only be resolved once all objects are spawned.
+fn object_values(properties: &tiled::Properties) -> tiled::Properties {
+    properties
+        .iter()
+        .filter(|(_, value)| matches!(value, tiled::PropertyValue::ObjectValue(_)))
+        .map(|(k, value)| (k.clone(), value.clone()))
+        .collect()
+}
+
+/// Inserts components wrapping the [`Entity`] of the objects referenced by object properties,
+/// like `struct Target(Entity)`. Returns the types of the inserted components.
+fn add_object_references(
+    properties: &tiled::Properties,
+    e: Entity,
+    object_entities: &HashMap<u32, Entity>,
+    type_registry: &impl Deref<Target = TypeRegistry>,
+    commands: &mut Commands,
+) -> Vec<TypeId> {
+    let mut inserted = Vec::new();
+    for (k, value) in properties.iter() {
+        // Object properties that were left empty in Tiled point to the id 0.
+        let tiled::PropertyValue::ObjectValue(object_id @ 1..) = value else {
+            continue;
+        };
+        let Some(type_registration) = find_registration(k, type_registry) else {
+            continue;
+        };
+        let type_info = type_registration.type_info();
+        let Some(reflect_component) = type_registration.data::<ReflectComponent>().cloned() else {
+            log::error!(
+                "Property {} on {:?} does not match a reflected component",
+                k,
+                e
+            );
+            continue;
+        };
+        if newtype_field(type_info) != Some(TypeId::of::<Entity>()) {
+            log::error!(
+                "Object property {} on {:?} requires a component with a single Entity field",
+                k,
+                e
+            );
+            continue;
+        }
+        let Some(target) = object_entities.get(object_id).copied() else {
+            log::warn!(
+                "Object {} referenced by property {} on {:?} was not found",
+                object_id,
+                k,
+                e
+            );
+            continue;
+        };
+        insert_newtype_component(commands, e, type_info, reflect_component, move |_| {
+            Box::new(target)
+        });
+        inserted.push(type_info.type_id());
+        log::info!("Added {}", type_info.type_path());
+    }
+    inserted
+}
+
+/// Inserts the component named after the class of an object.
+///
+/// The component has to either have no fields or implement [`Default`] and reflect it.
+fn add_class_component(
+    class: &str,
+    e: Entity,
+    type_registry: &impl Deref<Target = TypeRegistry>,
+    commands: &mut Commands,
+) {
+    if class.is_empty() {
+        return;
+    }
+    let Some(type_registration) = find_registration(class, type_registry) else {
+        log::info!("No component matches the class {} of {:?}", class, e);
+        return;
+    };
+    let type_info = type_registration.type_info();
+    let Some(reflect_component) = type_registration.data::<ReflectComponent>().cloned() else {
+        log::error!(
+            "Class {} of {:?} does not match a reflected component",
+            class,
+            e
+        );
+        return;
+    };
+    let component: Box<dyn Reflect> =
+        if let Some(reflect_default) = type_registration.data::<ReflectDefault>() {
+            reflect_default.default()
+        } else {
+            match type_info {
+                TypeInfo::Struct(info) if info.field_len() == 0 => {
+                    let mut component = DynamicStruct::default();
+                    component.set_represented_type(Some(type_info));
+                    Box::new(component)
+                }
+                TypeInfo::TupleStruct(info) if info.field_len() == 0 => {
+                    let mut component = DynamicTupleStruct::default();
+                    component.set_represented_type(Some(type_info));
+                    Box::new(component)
+                }
+                _ => {
+                    log::error!(
+                    "Component {} for the class of {:?} has fields but does not reflect Default",
+                    class,
+                    e
+                );
+                    return;
+                }
+            }
+        };
+    ParsedProperty::Insert {
+        reflect_component,
+        component: component.into(),
+    }
+    .apply(e, commands);
+    log::info!("Added {}", type_info.type_path());
+}
+
+/// Finds the type registration named by a property key or class.
+///
+/// Names containing `::` are matched against the full type path, like `mygame::combat::Health`,
+/// other names against the short type path. Short type paths shared by several registered types
+/// match none of them.
+fn find_registration<'a>(
+    name: &str,
+    type_registry: &'a TypeRegistry,
+) -> Option<&'a TypeRegistration> {
+    if name.contains("::") {
+        return type_registry.get_with_type_path(name);
+    }
+    if type_registry.is_ambiguous(name) {
+        let mut candidates: Vec<_> = type_registry
+            .iter()
+            .map(|registration| registration.type_info().type_path_table())
+            .filter(|type_path| type_path.short_path() == name)
+            .map(|type_path| type_path.path())
+            .collect();
+        candidates.sort_unstable();
+        log::error!(
+            "{} matches multiple registered types, use one of their full type paths instead: {}",
+            name,
+            candidates.join(", ")
+        );
+        return None;
+    }
+    type_registry.get_with_short_type_path(name)
+}
+
+fn deserialize_component(
+    ron_string: &str,
+    type_registry: &TypeRegistry,
+) -> Result<Box<dyn Reflect>, ron::Error> {
+    let mut deserializer = ron::de::Deserializer::from_str(ron_string)?;
+    ReflectDeserializer::new(type_registry).deserialize(&mut deserializer)
+}
+
+/// File properties are resolved relative to `map_dir`, the directory of the map file.
+fn add_properties(
+    properties: &std::collections::HashMap<String, tiled::PropertyValue>,
+    e: Entity,
+    map_dir: &Path,
+    type_registry: &impl Deref<Target = TypeRegistry>,
     commands: &mut Commands,
 ) {
+    for property in parse_properties(properties, e, map_dir, type_registry) {
+        property.apply(e, commands);
+    }
+}
+
+/// Parses the properties into components, `e` is only used for logging.
+fn parse_properties(
+    properties: &std::collections::HashMap<String, tiled::PropertyValue>,
+    e: Entity,
+    map_dir: &Path,
+    type_registry: &impl Deref<Target = TypeRegistry>,
+) -> Vec<ParsedProperty> {
+    let mut parsed_properties = Vec::new();
+    // Applied after the insertions, so a component inserted by another property of the same
+    // set is removed regardless of the order of the properties.
+    let mut removals = Vec::new();
     for (k, value) in properties.iter() {
-        if let Some(type_registration) = type_registry.get_with_short_type_path(k) {
+        let (name, is_resource) = match k.strip_prefix(RESOURCE_PREFIX) {
+            Some(name) => (name, true),
+            None => (k.as_str(), false),
+        };
+        if let Some(type_registration) = find_registration(name, type_registry) {
             let type_info = type_registration.type_info();
             let type_path = type_info.type_path();
+            // Types that are not components set a resource, which the prefix forces.
+            let reflect_component = match is_resource {
+                true => None,
+                false => type_registration.data::<ReflectComponent>().cloned(),
+            };
+            let reflect_resource = match reflect_component {
+                Some(_) => None,
+                None => type_registration.data::<ReflectResource>().cloned(),
+            };
+            if reflect_component.is_none() && reflect_resource.is_none() {
+                log::error!(
+                    "Property {} on {:?} sets {}, which does not reflect {}",
+                    k,
+                    e,
+                    type_path,
+                    if is_resource {
+                        "Resource"
+                    } else {
+                        "Component or Resource"
+                    }
+                );
+                continue;
+            }
+
+            // Components wrapping a single asset handle get the file loaded into it.
+            if let tiled::PropertyValue::FileValue(file) = value {
+                let loader = newtype_field(type_info).and_then(file_asset_loader);
+                if let (Some(loader), Some(reflect_component)) = (loader, reflect_component.clone())
+                {
+                    parsed_properties.push(ParsedProperty::InsertFile {
+                        reflect_component,
+                        type_info,
+                        loader,
+                        path: normalize_path(&map_dir.join(file)),
+                    });
+                    log::info!("Added {}", type_path);
+                    continue;
+                }
+            }
+
             let parsed_value = match value {
                 tiled::PropertyValue::BoolValue(b) => b.to_string(),
                 tiled::PropertyValue::FloatValue(f) => f.to_string(),
                 tiled::PropertyValue::IntValue(i) => i.to_string(),
                 tiled::PropertyValue::StringValue(s) => s.to_string(),
-                tiled::PropertyValue::ColorValue(c) => format!(
-                    "LinearRgba(red:{},green:{},blue:{}, alpha:{})",
-                    c.red as f32 / 255.0,
-                    c.green as f32 / 255.0,
-                    c.blue as f32 / 255.0,
-                    c.alpha as f32 / 255.0
-                ),
-                // tiled::PropertyValue::FileValue(_) => todo!(),
-                // tiled::PropertyValue::ObjectValue(_) => todo!(),
+                tiled::PropertyValue::ColorValue(c) => color_ron(c, newtype_field(type_info)),
+                // Other components get the path as a string.
+                tiled::PropertyValue::FileValue(file) => {
+                    format!(
+                        "{:?}",
+                        normalize_path(&map_dir.join(file)).to_string_lossy()
+                    )
+                }
+                // Resolved by `add_object_references` once all objects are spawned.
+                tiled::PropertyValue::ObjectValue(_) => continue,
                 _ => "".to_string(),
             }
             .trim()
             .to_string();
 
-            let matches : (bool,bool,&TypeInfo) = (parsed_value.starts_with('('), parsed_value.ends_with(')'), type_info);
+            let matches: (bool, bool, &TypeInfo) = (
+                parsed_value.starts_with('('),
+                parsed_value.ends_with(')'),
+                type_info,
+            );
 
-            let ron_string = match matches {
-                (false,false,TypeInfo::Enum(info)) =>{ 
-                    let variant = info.variant_names().iter().find(|v| v.to_lowercase().eq(&parsed_value.to_lowercase()));
-                    if variant.is_none() {
-                        log::error!("Failed to deserialize enum value {}\n Valid values: {:#?}", parsed_value, info.variant_names());
+            let is_string = matches!(value, tiled::PropertyValue::StringValue(_));
+            // Strings may contain the whole component in RON, like `(points: [1, 2])`, so that is
+            // tried first before wrapping the value.
+            let mut ron_strings = Vec::new();
+            if is_string {
+                ron_strings.push(format!("{{ \"{}\":{} }}", type_path, parsed_value));
+            }
+            match matches {
+                (false, false, TypeInfo::Enum(info)) => {
+                    if let Some(variant) = info
+                        .variant_names()
+                        .iter()
+                        .find(|v| v.to_lowercase().eq(&parsed_value.to_lowercase()))
+                    {
+                        ron_strings.push(format!("{{ \"{}\":{} }}", type_path, variant));
                     }
-                    format!("{{ \"{}\":{} }}", type_path, variant.unwrap())
-                },
-                (true, true, _) => format!("{{ \"{}\":{} }}", type_path, parsed_value),
-                (false, false, _) => format!("{{ \"{}\":({}) }}", type_path, parsed_value),
-                _ => {
-                    log::error!("Failed to deserialize component {}: {}", k, parsed_value);
+                }
+                (true, true, _) => {
+                    ron_strings.push(format!("{{ \"{}\":{} }}", type_path, parsed_value))
+                }
+                (false, false, _) => {
+                    ron_strings.push(format!("{{ \"{}\":({}) }}", type_path, parsed_value));
+                    // Plain text for tuple structs wrapping a `String`.
+                    if is_string {
+                        ron_strings.push(format!("{{ \"{}\":({:?}) }}", type_path, parsed_value));
+                    }
+                }
+                _ => {}
+            }
+            ron_strings.dedup();
+
+            let mut result = None;
+            for ron_string in &ron_strings {
+                match deserialize_component(ron_string, type_registry) {
+                    Ok(component) => {
+                        result = Some(Ok(component));
+                        break;
+                    }
+                    Err(err) => result = Some(Err((ron_string, err))),
+                }
+            }
+            let component = match (result, type_info) {
+                (Some(Ok(component)), _) => component,
+                (_, TypeInfo::Enum(info)) => {
+                    log::error!(
+                        "Failed to deserialize enum value {} of property {} on {:?}\n Valid values: {:#?}",
+                        parsed_value,
+                        k,
+                        e,
+                        info.variant_names()
+                    );
+                    continue;
+                }
+                (Some(Err((ron_string, err))), _) => {
+                    log::error!(
+                        "Failed to deserialize property {} on {:?} from {}: {}",
+                        k,
+                        e,
+                        ron_string,
+                        err
+                    );
+                    continue;
+                }
+                (None, _) => {
+                    log::error!(
+                        "Failed to deserialize component {} on {:?}: {}",
+                        k,
+                        e,
+                        parsed_value
+                    );
                     continue;
                 }
             };
 
-            let mut deserializer = ron::de::Deserializer::from_str(&ron_string).unwrap();
-            let reflect_deserializer = ReflectDeserializer::new(type_registry);
-            let component = reflect_deserializer
-                .deserialize(&mut deserializer)
-                .unwrap_or_else(|_| {
-                    panic!(
-                        "Failed to deserialize component {}: {}",
-                        type_path,
-                        ron_string
-                    )
-                });
-            let result = type_registry
-                .get(type_registration.type_id())
-                .unwrap()
-                .data::<ReflectComponent>()
-                .unwrap()
-                .clone();
-
-            commands.add(move |world: &mut World| {
-                let type_registry = world.resource::<AppTypeRegistry>().clone();
-                let type_registry = type_registry.read();
-                let mut entity_mut = world.entity_mut(e);
-                result.insert(&mut entity_mut, &*component, &type_registry);
+            parsed_properties.push(match (reflect_component, reflect_resource) {
+                (Some(reflect_component), _) => ParsedProperty::Insert {
+                    reflect_component,
+                    component: component.into(),
+                },
+                (None, Some(reflect_resource)) => ParsedProperty::InsertResource {
+                    reflect_resource,
+                    resource: component.into(),
+                },
+                (None, None) => unreachable!("Properties of other types are skipped above"),
             });
             log::info!("Added {}", type_registration.type_info().type_path());
         } else if k.starts_with(REMOVE_PREFIX) {
-            let type_registration =
-                type_registry.get_with_short_type_path(k.strip_prefix(REMOVE_PREFIX).unwrap());
-            if type_registration.is_none() {
-                log::error!("Failed to deserialize component");
+            let Some(type_registration) = k
+                .strip_prefix(REMOVE_PREFIX)
+                .and_then(|name| find_registration(name, type_registry))
+            else {
+                log::error!(
+                    "Failed to find component to remove for property {} on {:?}",
+                    k,
+                    e
+                );
+                continue;
+            };
+            let type_path = type_registration.type_info().type_path();
+            if type_registration.data::<ReflectComponent>().is_none() {
+                log::error!(
+                    "Failed to remove {} for property {} on {:?}, it does not reflect Component",
+                    type_path,
+                    k,
+                    e
+                );
                 continue;
             }
-            let type_registration = type_registration.unwrap();
-            commands
-                .entity(e)
-                .remove_reflect(type_registration.type_info().type_path());
-            log::info!("Removed {}", type_registration.type_info().type_path());
+            // `remove_reflect` only finds components by their full type path.
+            removals.push(ParsedProperty::Remove(type_path));
+            log::info!("Removed {}", type_path);
+        } else if !BUILTIN_PROPERTIES.contains(&k.as_str()) {
+            parsed_properties.push(ParsedProperty::Unresolved {
+                name: k.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+    parsed_properties.extend(removals);
+    parsed_properties
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::Duration;
+
+    use super::*;
+    use crate::headless::{spawn_test_app, update_until_spawned};
+
+    /// Spawns the map at `path` in the assets folder and waits until it is spawned.
+    fn spawn_map(app: &mut App, path: &str) -> Entity {
+        let handle: Handle<TiledMap> = app.world().resource::<AssetServer>().load(path.to_owned());
+        let map = app.world_mut().spawn(TiledMapBundle::new(handle)).id();
+        assert!(
+            update_until_spawned(app, map, Duration::from_secs(10)),
+            "{path} was not spawned"
+        );
+        map
+    }
+
+    /// Returns the image of a tileset which has a single one.
+    fn tileset_image(tiled_map: &TiledMap, tileset_index: usize) -> &Handle<Image> {
+        // With the `atlas` feature textures are always single images.
+        #[allow(irrefutable_let_patterns)]
+        let TilemapTexture::Single(texture) = &tiled_map.tilemap_textures[&tileset_index] else {
+            panic!("Tileset {tileset_index} does not have a single image");
+        };
+        texture
+    }
+
+    #[test]
+    fn hex_coord_system_matches_the_stagger_of_the_map() {
+        use tiled::{StaggerAxis, StaggerIndex};
+        // Without flipping, the tile at `TilePos` (0, 0) is the first tile in Tiled.
+        let cases = [
+            (StaggerAxis::X, StaggerIndex::Odd, HexCoordSystem::ColumnOdd),
+            (
+                StaggerAxis::X,
+                StaggerIndex::Even,
+                HexCoordSystem::ColumnEven,
+            ),
+            (StaggerAxis::Y, StaggerIndex::Odd, HexCoordSystem::RowOdd),
+            (StaggerAxis::Y, StaggerIndex::Even, HexCoordSystem::RowEven),
+        ];
+        for (axis, index, expected) in cases {
+            assert_eq!(
+                hex_coord_system(axis, index, IVec2::ZERO, false),
+                expected,
+                "{axis:?} {index:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn hex_coord_system_follows_the_flipped_y_axis() {
+        use tiled::{StaggerAxis, StaggerIndex};
+        // Flipping starts the tilemap at the last row, the 10th one here, and shifts staggered
+        // columns up instead of down.
+        let first_tile = IVec2::new(0, 9);
+        let cases = [
+            (
+                StaggerAxis::X,
+                StaggerIndex::Odd,
+                HexCoordSystem::ColumnEven,
+            ),
+            (
+                StaggerAxis::X,
+                StaggerIndex::Even,
+                HexCoordSystem::ColumnOdd,
+            ),
+            (StaggerAxis::Y, StaggerIndex::Odd, HexCoordSystem::RowEven),
+            (StaggerAxis::Y, StaggerIndex::Even, HexCoordSystem::RowOdd),
+        ];
+        for (axis, index, expected) in cases {
+            assert_eq!(
+                hex_coord_system(axis, index, first_tile, true),
+                expected,
+                "{axis:?} {index:?}"
+            );
+        }
+    }
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Scores {
+        values: Vec<i32>,
+    }
+
+    #[derive(Reflect, Default, Debug, PartialEq)]
+    struct Stats {
+        strength: i32,
+        name: String,
+    }
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Character {
+        stats: Stats,
+        speed: f32,
+    }
+
+    /// Applies `properties` to a new entity of `app`, whose types have to be registered.
+    fn apply_properties(app: &mut App, properties: &[(&str, tiled::PropertyValue)]) -> Entity {
+        let properties = properties
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        let world = app.world_mut();
+        let e = world.spawn_empty().id();
+        let type_registry = world.resource::<AppTypeRegistry>().clone();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, world);
+        add_properties(
+            &properties,
+            e,
+            Path::new(""),
+            &type_registry.read(),
+            &mut commands,
+        );
+        queue.apply(world);
+        e
+    }
+
+    #[test]
+    fn string_properties_set_vec_fields() {
+        let mut app = App::new();
+        app.register_type::<Scores>();
+        let e = apply_properties(
+            &mut app,
+            &[(
+                "Scores",
+                tiled::PropertyValue::StringValue("(values: [1, -2, 3])".to_string()),
+            )],
+        );
+        assert_eq!(
+            app.world().get::<Scores>(e),
+            Some(&Scores {
+                values: vec![1, -2, 3]
+            })
+        );
+    }
+
+    #[test]
+    fn string_properties_set_nested_structs() {
+        let mut app = App::new();
+        app.register_type::<Character>();
+        let e = apply_properties(
+            &mut app,
+            &[(
+                "Character",
+                tiled::PropertyValue::StringValue(
+                    "(stats: (strength: 5, name: \"Bob\"), speed: 1.5)".to_string(),
+                ),
+            )],
+        );
+        assert_eq!(
+            app.world().get::<Character>(e),
+            Some(&Character {
+                stats: Stats {
+                    strength: 5,
+                    name: "Bob".to_string(),
+                },
+                speed: 1.5,
+            })
+        );
+    }
+
+    #[test]
+    fn remove_properties_remove_components_of_the_same_set() {
+        let scores = tiled::PropertyValue::StringValue("(values: [1])".to_string());
+        let empty = tiled::PropertyValue::StringValue(String::new());
+        for remove in [
+            "remove:Scores",
+            "remove:bevy_tiled_blueprints::tests::Scores",
+        ] {
+            let mut app = App::new();
+            app.register_type::<Scores>().register_type::<Character>();
+            let e = apply_properties(
+                &mut app,
+                &[
+                    ("Scores", scores.clone()),
+                    (
+                        "Character",
+                        tiled::PropertyValue::StringValue(
+                            "(stats: (strength: 1, name: \"\"), speed: 0.0)".into(),
+                        ),
+                    ),
+                    (remove, empty.clone()),
+                ],
+            );
+            assert!(app.world().get::<Scores>(e).is_none(), "{remove}");
+            // Only the named component is removed.
+            assert!(app.world().get::<Character>(e).is_some(), "{remove}");
+        }
+    }
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Tint(Srgba);
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component)]
+    struct Glow(LinearRgba);
+
+    /// A `#AARRGGBB` color of Tiled, an orange at half opacity.
+    fn half_transparent_orange() -> tiled::PropertyValue {
+        tiled::PropertyValue::ColorValue("#80ff4000".parse().unwrap())
+    }
+
+    #[test]
+    fn color_properties_keep_srgb_channels() {
+        let mut app = App::new();
+        app.register_type::<Tint>();
+        let e = apply_properties(&mut app, &[("Tint", half_transparent_orange())]);
+        let Tint(color) = app.world().get::<Tint>(e).unwrap();
+        assert_eq!(color.red, 1.0);
+        assert_eq!(color.green, 64.0 / 255.0);
+        assert_eq!(color.blue, 0.0);
+        assert_eq!(color.alpha, 128.0 / 255.0);
+    }
+
+    #[test]
+    fn color_properties_convert_to_linear_channels() {
+        let mut app = App::new();
+        app.register_type::<Glow>();
+        let e = apply_properties(&mut app, &[("Glow", half_transparent_orange())]);
+        let Glow(color) = app.world().get::<Glow>(e).unwrap();
+        let linear_green = ((64.0 / 255.0 + 0.055) / 1.055f32).powf(2.4);
+        assert!((color.red - 1.0).abs() < 1e-6);
+        assert!((color.green - linear_green).abs() < 1e-6);
+        assert_eq!(color.blue, 0.0);
+        // Alpha is not gamma encoded.
+        assert_eq!(color.alpha, 128.0 / 255.0);
+    }
+
+    #[test]
+    fn loads_maps_at_the_assets_root() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "map.tmx");
+
+        let world = app.world();
+        let handle = world.get::<Handle<TiledMap>>(map).unwrap();
+        let tiled_map = world.resource::<Assets<TiledMap>>().get(handle).unwrap();
+        let texture = tileset_image(tiled_map, 0);
+        assert_eq!(
+            texture.path().unwrap().path(),
+            Path::new("textures/colony-grounds-ready.png")
+        );
+    }
+
+    #[test]
+    fn despawning_a_map_removes_its_tiles() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "map.tmx");
+        let world = app.world_mut();
+        assert_eq!(world.query::<&TilePos>().iter(world).count(), 50 * 30);
+
+        world.commands().despawn_tiled_map(map);
+        world.flush_commands();
+        app.update();
+
+        let world = app.world_mut();
+        assert!(world.get_entity(map).is_none());
+        assert_eq!(world.query::<&TilePos>().iter(world).count(), 0);
+        assert_eq!(world.query::<&TiledObject>().iter(world).count(), 0);
+        assert_eq!(world.query::<&TileLayerMarker>().iter(world).count(), 0);
+    }
+
+    #[test]
+    fn reloading_a_map_replaces_its_entities() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "map.tmx");
+        let mut previous_layers = Vec::new();
+
+        for _ in 0..2 {
+            app.world_mut().send_event(ReloadTiledMaps);
+            app.update();
+
+            let world = app.world_mut();
+            let layers: Vec<Entity> = world
+                .get::<TiledLayersStorage>(map)
+                .unwrap()
+                .storage
+                .values()
+                .copied()
+                .collect();
+            assert_eq!(layers.len(), 2);
+            assert!(layers
+                .iter()
+                .all(|layer| world.get_entity(*layer).is_some()));
+            assert!(layers.iter().all(|layer| !previous_layers.contains(layer)));
+            assert_eq!(world.query::<&ObjectLayer>().iter(world).count(), 1);
+            assert_eq!(world.query::<&TileLayerMarker>().iter(world).count(), 1);
+            assert_eq!(world.query::<&TilePos>().iter(world).count(), 50 * 30);
+            assert_eq!(world.query::<&TiledObject>().iter(world).count(), 3);
+            previous_layers = layers;
+        }
+    }
+
+    /// Largest numbers of tiles, animated tiles and tile layers seen between
+    /// [`TiledSystems::Process`] and [`TiledSystems::Cleanup`].
+    #[derive(Resource, Default)]
+    struct MostTiles(usize, usize, usize);
+
+    fn count_most_tiles(
+        tiles: Query<&TilePos>,
+        animated: Query<&AnimatedTile>,
+        layers: Query<&TileLayerMarker>,
+        mut most: ResMut<MostTiles>,
+    ) {
+        most.0 = most.0.max(tiles.iter().count());
+        most.1 = most.1.max(animated.iter().count());
+        most.2 = most.2.max(layers.iter().count());
+    }
+
+    #[test]
+    fn reloaded_maps_never_show_old_and_new_tiles_together() {
+        let mut app = spawn_test_app("assets");
+        app.init_resource::<MostTiles>().add_systems(
+            Update,
+            count_most_tiles
+                .after(TiledSystems::Process)
+                .before(TiledSystems::Cleanup),
+        );
+        spawn_map(&mut app, "animated.tmx");
+        for _ in 0..2 {
+            app.world_mut().send_event(ReloadTiledMaps);
+            app.update();
+            app.update();
+        }
+
+        let most = app.world().resource::<MostTiles>();
+        assert_eq!((most.0, most.1, most.2), (20 * 12, 20 * 12, 1));
+    }
+
+    #[test]
+    fn tiles_keep_the_flips_of_tiled() {
+        let mut app = spawn_test_app("assets");
+        spawn_map(&mut app, "flips.tmx");
+
+        let world = app.world_mut();
+        let mut flips: Vec<(u32, TileFlip)> = world
+            .query::<(&TilePos, &TileFlip)>()
+            .iter(world)
+            .map(|(pos, flip)| (pos.x, *flip))
+            .collect();
+        flips.sort_by_key(|(x, _)| *x);
+        // The tiles of flips.tmx go through the bits of the horizontal, vertical and
+        // anti-diagonal flips in this order, like a binary counter.
+        let expected: Vec<(u32, TileFlip)> = (0..8)
+            .map(|i| {
+                let flip = TileFlip {
+                    x: i & 1 != 0,
+                    y: i & 2 != 0,
+                    d: i & 4 != 0,
+                };
+                (i, flip)
+            })
+            .collect();
+        assert_eq!(flips, expected);
+    }
+
+    #[test]
+    fn layers_are_spawned_once_regardless_of_the_tilesets() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "tests/two_tilesets.tmx");
+
+        let world = app.world_mut();
+        assert_eq!(world.query::<&TiledTileset>().iter(world).count(), 2);
+        assert_eq!(
+            world.get::<TiledLayersStorage>(map).unwrap().storage.len(),
+            2
+        );
+        assert_eq!(world.query::<&ObjectLayer>().iter(world).count(), 1);
+        assert_eq!(world.query::<&TiledObject>().iter(world).count(), 1);
+        assert_eq!(world.query::<&TileLayerMarker>().iter(world).count(), 1);
+        // The tiles of the second tileset are in a tilemap child of the layer.
+        assert_eq!(world.query::<&TileStorage>().iter(world).count(), 2);
+        assert_eq!(world.query::<&TilePos>().iter(world).count(), 7);
+    }
+
+    #[test]
+    fn despawned_layers_are_removed_from_the_storage() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "map.tmx");
+        let other_map = spawn_map(&mut app, "objects_only.tmx");
+
+        let world = app.world_mut();
+        let storage = world.get::<TiledLayersStorage>(map).unwrap();
+        let objects = storage.layer_by_name("objects").unwrap();
+        let tiles = storage.layer_by_name("bg").unwrap();
+        // One layer is despawned directly and the other one marked for removal.
+        world.entity_mut(objects).despawn_recursive();
+        world.entity_mut(tiles).insert(RemoveMap);
+        app.update();
+
+        let world = app.world_mut();
+        assert!(world.get_entity(map).is_some());
+        let storage = world.get::<TiledLayersStorage>(map).unwrap();
+        assert!(storage.storage.is_empty());
+        assert!(storage.names.is_empty());
+        assert_eq!(world.query::<&TilePos>().iter(world).count(), 0);
+        let other_storage = world.get::<TiledLayersStorage>(other_map).unwrap();
+        assert_eq!(other_storage.storage.len(), 1);
+        assert!(other_storage.layer_by_name("triggers").is_some());
+    }
+
+    /// Position in the world of the center of the tile at `tile_pos` in the tile layer `layer`.
+    fn tile_world_position(world: &World, layer: Entity, tile_pos: TilePos) -> Vec2 {
+        let grid_size = world.get::<TilemapGridSize>(layer).unwrap();
+        let map_type = world.get::<TilemapType>(layer).unwrap();
+        world
+            .get::<GlobalTransform>(layer)
+            .unwrap()
+            .transform_point(tile_pos.center_in_world(grid_size, map_type).extend(0.0))
+            .truncate()
+    }
+
+    /// Position in the world of the object with the id `id` in Tiled, in a layer of `map`.
+    fn object_world_position(world: &mut World, map: Entity, id: u32) -> Vec2 {
+        let mut objects = world.query::<(&TiledObject, &Parent, &GlobalTransform)>();
+        objects
+            .iter(world)
+            .find(|(object, layer, _)| {
+                object.id == id && world.get::<Parent>(layer.get()).map(Parent::get) == Some(map)
+            })
+            .map(|(_, _, transform)| transform.translation().truncate())
+            .unwrap()
+    }
+
+    #[test]
+    fn translated_maps_move_their_tiles_and_objects() {
+        let mut app = spawn_test_app("assets");
+        let handle: Handle<TiledMap> = app
+            .world()
+            .resource::<AssetServer>()
+            .load("tests/two_tilesets.tmx");
+        let map = app
+            .world_mut()
+            .spawn(TiledMapBundle::new(handle).at(Transform::from_xyz(1000.0, 0.0, 0.0)))
+            .id();
+        assert!(update_until_spawned(&mut app, map, Duration::from_secs(10)));
+        // Global transforms are propagated at the end of the frame the entities are spawned in.
+        app.update();
+
+        let world = app.world_mut();
+        let layer = world
+            .get::<TiledLayersStorage>(map)
+            .unwrap()
+            .layer_by_name("ground")
+            .unwrap();
+        // The map is 4 by 2 tiles of 16 pixels, centered on its transform.
+        let tile = tile_world_position(world, layer, TilePos { x: 0, y: 0 });
+        assert!(tile.distance(Vec2::new(976.0, -8.0)) < 1e-3, "{tile}");
+        let tile = tile_world_position(world, layer, TilePos { x: 3, y: 1 });
+        assert!(tile.distance(Vec2::new(1024.0, 8.0)) < 1e-3, "{tile}");
+        // The chest is 24 pixels right of and 8 below the top-left corner.
+        let chest = object_world_position(world, map, 1);
+        assert!(chest.distance(Vec2::new(992.0, 8.0)) < 1e-3, "{chest}");
+    }
+
+    #[test]
+    fn y_down_maps_are_mirrored_back_upright() {
+        let mut app = spawn_test_app("assets");
+        let handle: Handle<TiledMap> = app.world().resource::<AssetServer>().load_with_settings(
+            "tests/two_tilesets.tmx",
+            |settings: &mut TiledMapSettings| settings.y_axis = YAxis::YDown,
+        );
+        let map = app
+            .world_mut()
+            .spawn(TiledMapBundle::new(handle).at(Transform::from_xyz(1000.0, 0.0, 0.0)))
+            .id();
+        assert!(update_until_spawned(&mut app, map, Duration::from_secs(10)));
+        app.update();
+
+        let world = app.world_mut();
+        assert_eq!(world.get::<Transform>(map).unwrap().scale.y, -1.0);
+        let layer = world
+            .get::<TiledLayersStorage>(map)
+            .unwrap()
+            .layer_by_name("ground")
+            .unwrap();
+        // Tiles land where they do with the Y axis of Bevy, without mirroring their tilemap.
+        assert!(!is_mirrored(world.get::<GlobalTransform>(layer).unwrap()));
+        let tile = tile_world_position(world, layer, TilePos { x: 0, y: 0 });
+        assert!(tile.distance(Vec2::new(976.0, -8.0)) < 1e-3, "{tile}");
+        let tile = tile_world_position(world, layer, TilePos { x: 3, y: 1 });
+        assert!(tile.distance(Vec2::new(1024.0, 8.0)) < 1e-3, "{tile}");
+        let chest = object_world_position(world, map, 1);
+        assert!(chest.distance(Vec2::new(992.0, 8.0)) < 1e-3, "{chest}");
+        // The space of the map keeps the Y axis of Tiled, so the chest 8 pixels below the top of
+        // the 32 pixels high map is 8 pixels above its center.
+        let map_transform = world.get::<GlobalTransform>(map).unwrap().affine();
+        let chest = map_transform.inverse().transform_point3(chest.extend(0.0));
+        assert!(
+            chest.truncate().distance(Vec2::new(-8.0, -8.0)) < 1e-3,
+            "{chest}"
+        );
+    }
+
+    /// Returns whether a tile of the tile layer `layer` is centered on `position`.
+    fn has_tile_at(world: &World, layer: Entity, position: Vec2) -> bool {
+        let storage = world.get::<TileStorage>(layer).unwrap();
+        (0..storage.size.x)
+            .flat_map(|x| (0..storage.size.y).map(move |y| TilePos { x, y }))
+            .filter(|tile_pos| storage.get(tile_pos).is_some())
+            .any(|tile_pos| tile_world_position(world, layer, tile_pos).distance(position) < 1e-3)
+    }
+
+    #[test]
+    fn markers_land_on_their_tile_in_diamond_maps() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "isometric.tmx");
+        app.update();
+
+        let world = app.world_mut();
+        let layer = world
+            .get::<TiledLayersStorage>(map)
+            .unwrap()
+            .layer_by_name("ground")
+            .unwrap();
+        // The marker is at the center of the tile (2, 3) of Tiled, 80 pixels right of and 48
+        // below the top-left corner of the 192 by 96 pixels map.
+        let marker = object_world_position(world, map, 1);
+        assert!(marker.distance(Vec2::new(-16.0, 0.0)) < 1e-3, "{marker}");
+        let tile = tile_world_position(world, layer, TilePos { x: 2, y: 2 });
+        assert!(marker.distance(tile) < 1e-3, "{marker} != {tile}");
+    }
+
+    #[test]
+    fn markers_land_on_their_tile_in_staggered_maps() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "staggered.tmx");
+        app.update();
+
+        let world = app.world_mut();
+        let layer = world
+            .get::<TiledLayersStorage>(map)
+            .unwrap()
+            .layer_by_name("ground")
+            .unwrap();
+        // The marker is at the center of the tile (2, 3) of Tiled, 96 pixels right of and 32
+        // below the top-left corner of the 208 by 88 pixels map.
+        let marker = object_world_position(world, map, 1);
+        assert!(marker.distance(Vec2::new(-8.0, 12.0)) < 1e-3, "{marker}");
+        assert!(has_tile_at(world, layer, marker), "no tile at {marker}");
+    }
+
+    #[test]
+    fn tall_isometric_tiles_are_sorted_on_demand() {
+        let cases = [
+            ("tall_tiles_right_down.tmx", RenderOrder::RightDown),
+            ("tall_tiles_right_up.tmx", RenderOrder::RightUp),
+        ];
+        for (path, render_order) in cases {
+            let mut app = spawn_test_app("assets/tests");
+            let map = spawn_map(&mut app, path);
+            app.update();
+
+            let world = app.world_mut();
+            let handle = world.get::<Handle<TiledMap>>(map).unwrap().clone();
+            let tiled_map = world.resource::<Assets<TiledMap>>().get(&handle).unwrap();
+            assert_eq!(tiled_map.render_order, render_order, "{path}");
+            let layers = world.get::<TiledLayersStorage>(map).unwrap();
+            let ground = layers.layer_by_name("ground").unwrap();
+            let sorted = layers.layer_by_name("sorted");
+
+            // Whatever the render order, a single chunk draws the rows from the bottom.
+            let settings = world.get::<TilemapRenderSettings>(ground).unwrap();
+            assert_eq!(settings.render_chunk_size, UVec2::new(4, 3), "{path}");
+            assert!(!settings.y_sort, "{path}");
+            // Every tile is sorted on its own with the `y_sort` property.
+            if let Some(sorted) = sorted {
+                let settings = world.get::<TilemapRenderSettings>(sorted).unwrap();
+                assert_eq!(settings.render_chunk_size, UVec2::ONE, "{path}");
+                assert!(settings.y_sort, "{path}");
+            }
+        }
+    }
+
+    #[test]
+    fn json_maps_match_their_tmx_twin() {
+        let parse = |path: &str| {
+            let bytes = std::fs::read(path).unwrap();
+            parse_map(&bytes, Path::new(path), None, None).unwrap().0
+        };
+        let tmx = parse("assets/tests/json_twin.tmx");
+        let tmj = parse("assets/tests/json_twin.tmj");
+        assert_eq!(tmj.properties, tmx.properties);
+        assert_eq!(
+            (tmj.width, tmj.height, tmj.tile_width, tmj.tile_height),
+            (tmx.width, tmx.height, tmx.tile_width, tmx.tile_height)
+        );
+
+        // Tilesets are read from different files, everything else matches, Wang sets included.
+        assert_eq!(tmj.tilesets().len(), tmx.tilesets().len());
+        for (tmj_tileset, tmx_tileset) in tmj.tilesets().iter().zip(tmx.tilesets()) {
+            let mut tmj_tileset = tmj_tileset.as_ref().clone();
+            tmj_tileset.source.clone_from(&tmx_tileset.source);
+            assert_eq!(&tmj_tileset, tmx_tileset.as_ref());
+        }
+        assert_eq!(tmj.tilesets()[0].wang_sets.len(), 1);
+
+        let (mut tmj_layers, mut tmx_layers) = (Vec::new(), Vec::new());
+        flatten_layers(tmj.layers(), None, &mut tmj_layers);
+        flatten_layers(tmx.layers(), None, &mut tmx_layers);
+        assert_eq!(tmj_layers.len(), tmx_layers.len());
+        // Layers holding tile objects of templates point to the tileset of the template, so
+        // only the other layers are compared as a whole.
+        let header = |layer: &tiled::Layer| {
+            let offset = (layer.offset_x, layer.offset_y);
+            (
+                layer.id(),
+                layer.name.clone(),
+                layer.visible,
+                offset,
+                layer.opacity,
+            )
+        };
+        let object = |object: tiled::Object| {
+            let tile = object
+                .tile_data()
+                .map(|tile| (tile.id(), tile.flip_h, tile.flip_v, tile.flip_d));
+            let position = (object.x, object.y, object.rotation);
+            let data = (object.shape.clone(), object.properties.clone(), tile);
+            (
+                object.id(),
+                object.name.clone(),
+                object.user_type.clone(),
+                position,
+                data,
+            )
+        };
+        for ((tmj_layer, tmj_parent), (tmx_layer, tmx_parent)) in tmj_layers.iter().zip(&tmx_layers)
+        {
+            assert_eq!(tmj_parent, tmx_parent);
+            assert_eq!(header(tmj_layer), header(tmx_layer));
+            assert_eq!(tmj_layer.properties, tmx_layer.properties);
+            match (tmj_layer.layer_type(), tmx_layer.layer_type()) {
+                (tiled::LayerType::Objects(a), tiled::LayerType::Objects(b)) => {
+                    let a: Vec<_> = a.objects().map(object).collect();
+                    let b: Vec<_> = b.objects().map(object).collect();
+                    assert_eq!(a, b);
+                }
+                (tiled::LayerType::Group(_), tiled::LayerType::Group(_)) => {}
+                _ => assert_eq!(**tmj_layer, **tmx_layer),
+            }
+        }
+    }
+
+    #[test]
+    fn maps_return_their_properties() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "tests/groups.tmx");
+
+        let world = app.world();
+        let maps = world.resource::<Assets<TiledMap>>();
+        let tiled_map = maps
+            .get(world.get::<Handle<TiledMap>>(map).unwrap())
+            .unwrap();
+        assert_eq!(
+            tiled_map.property("difficulty"),
+            Some(&tiled::PropertyValue::IntValue(3))
+        );
+        assert_eq!(tiled_map.property("missing"), None);
+    }
+
+    #[test]
+    fn maps_return_the_names_of_their_layers_in_the_order_of_the_storage() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "tests/groups.tmx");
+
+        let world = app.world();
+        let maps = world.resource::<Assets<TiledMap>>();
+        let tiled_map = maps.get(world.get::<Handle<TiledMap>>(map).unwrap());
+        let names: Vec<&str> = tiled_map.unwrap().layer_names().collect();
+        assert_eq!(names, ["background", "world", "enemies", "items", "ui"]);
+        let storage = world.get::<TiledLayersStorage>(map).unwrap();
+        for (index, name) in names.iter().enumerate() {
+            let layer = storage.layer_by_name(name);
+            assert!(layer.is_some(), "{name} was not spawned");
+            assert_eq!(storage.storage.get(&(index as u32)).copied(), layer);
+        }
+    }
+
+    /// Shape of the object with the id `id` in Tiled.
+    fn object_shape_of(world: &mut World, id: u32) -> shapes::TiledObjectShape {
+        let mut objects = world.query::<(&TiledObject, &shapes::TiledObjectShape)>();
+        objects
+            .iter(world)
+            .find(|(object, _)| object.id == id)
+            .map(|(_, shape)| shape.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn rectangles_span_down_from_their_top_left_corner() {
+        let mut app = spawn_test_app("assets");
+        spawn_map(&mut app, "objects_only.tmx");
+
+        // The door is 16 by 32 pixels.
+        assert_eq!(
+            object_shape_of(app.world_mut(), 2),
+            shapes::TiledObjectShape::Rect {
+                width: 16.0,
+                height: 32.0,
+                center: Vec2::new(8.0, -16.0),
+            }
+        );
+    }
+
+    #[test]
+    fn tile_objects_span_up_from_their_bottom_left_corner() {
+        let mut app = spawn_test_app("assets");
+        spawn_map(&mut app, "templates.tmx");
+
+        // BigCrate is a tile object resized to 32 by 32 pixels.
+        assert_eq!(
+            object_shape_of(app.world_mut(), 3),
+            shapes::TiledObjectShape::Rect {
+                width: 32.0,
+                height: 32.0,
+                center: Vec2::new(16.0, 16.0),
+            }
+        );
+    }
+
+    #[test]
+    fn rotated_objects_turn_their_shape_around_their_position() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "tests/rotated.tmx");
+        app.update();
+
+        // The plank is 32 by 16 pixels, turned clockwise by 90 degrees around its top-left
+        // corner 16 pixels right of and below the top-left corner of the 64 by 64 pixels map.
+        let world = app.world_mut();
+        let position = object_world_position(world, map, 1);
+        assert!(
+            position.distance(Vec2::new(-16.0, 16.0)) < 1e-3,
+            "{position}"
+        );
+        let shapes::TiledObjectShape::Rect { center, .. } = object_shape_of(world, 1) else {
+            panic!("the plank is not a rectangle");
+        };
+        let mut objects = world.query_filtered::<&GlobalTransform, With<TiledObject>>();
+        let transform = objects.single(world);
+        let center = transform.transform_point(center.extend(0.0)).truncate();
+        assert!(center.distance(Vec2::new(-24.0, 0.0)) < 1e-3, "{center}");
+    }
+
+    #[test]
+    fn objects_follow_the_draw_order_of_their_layer() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "tests/draw_order.tmx");
+
+        let world = app.world_mut();
+        let storage = world.get::<TiledLayersStorage>(map).unwrap();
+        let (topdown, index) = (
+            storage.layer_by_name("topdown").unwrap(),
+            storage.layer_by_name("index").unwrap(),
+        );
+        // The collision shapes of the tileset have a draw order of their own.
+        assert_eq!(world.get(topdown), Some(&ObjectDrawOrder::TopDown));
+        assert_eq!(world.get(index), Some(&ObjectDrawOrder::Index));
+        let mut objects = world.query::<(&TiledObject, &Transform)>();
+        let z: HashMap<u32, f32> = objects
+            .iter(world)
+            .map(|(object, transform)| (object.id, transform.translation.z))
+            .collect();
+        // Lower objects are in front in the topdown order, later ones in the index order.
+        assert!(z[&1] > z[&2], "{z:?}");
+        assert!(z[&4] > z[&3], "{z:?}");
+    }
+
+    #[test]
+    fn maps_are_not_spawned_once_their_handle_is_replaced() {
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "objects_only.tmx");
+
+        // The replacement never loads, so the map is never respawned.
+        let handle: Handle<TiledMap> = app.world().resource::<AssetServer>().load("missing.tmx");
+        app.world_mut().entity_mut(map).insert(handle);
+        assert!(!update_until_spawned(
+            &mut app,
+            map,
+            Duration::from_millis(200)
+        ));
+    }
+
+    #[test]
+    fn properties_of_types_without_component_or_resource_are_skipped() {
+        #[derive(Reflect)]
+        struct Speed(f32);
+
+        let type_registry = AppTypeRegistry::default();
+        type_registry.write().register::<Speed>();
+        let properties = [("Speed".to_string(), tiled::PropertyValue::FloatValue(2.0))];
+        let parsed = parse_properties(
+            &properties.into_iter().collect(),
+            Entity::PLACEHOLDER,
+            Path::new(""),
+            &type_registry.read(),
+        );
+        assert!(parsed.is_empty());
+    }
+
+    #[test]
+    fn tilesets_and_images_are_read_from_the_archive() {
+        use crate::archive::tests::{tar, tar_entry};
+        // Only the map is in the assets folder.
+        let tileset = br#"<?xml version="1.0" encoding="UTF-8"?>
+<tileset version="1.10" tiledversion="1.10.2" name="blocks" tilewidth="32" tileheight="16" tilecount="1" columns="1">
+ <image source="blocks.png" width="32" height="16"/>
+</tileset>"#;
+        let image = std::fs::read("assets/textures/iso_tile.png").unwrap();
+        let archive = tar([
+            tar_entry("archived/blocks.tsx", b'0', tileset),
+            tar_entry("archived/blocks.png", b'0', &image),
+        ]);
+        let plugin = TiledBlueprintsPlugin {
+            loader: TiledLoader::with_archive(&archive).unwrap(),
+            ..Default::default()
+        };
+        let mut app = crate::headless::spawn_test_app_with_plugin("assets/tests", plugin);
+        let map = spawn_map(&mut app, "archived.tmx");
+
+        let world = app.world_mut();
+        assert_eq!(world.query::<&TilePos>().iter(world).count(), 3);
+        let handle = world.get::<Handle<TiledMap>>(map).unwrap();
+        let tiled_map = world.resource::<Assets<TiledMap>>().get(handle).unwrap();
+        let texture = tileset_image(tiled_map, 0);
+        // Images of the archive are decoded into labeled assets of the map.
+        let path = texture.path().unwrap();
+        assert_eq!(path.path(), Path::new("archived.tmx"));
+        assert_eq!(path.label(), Some("archived/blocks.png"));
+        let image = world.resource::<Assets<Image>>().get(texture).unwrap();
+        assert_eq!(image.size(), UVec2::new(32, 16));
+    }
+
+    #[test]
+    fn embedded_images_are_decoded_into_labeled_assets() {
+        let mut app = spawn_test_app("assets/tests");
+        let map = spawn_map(&mut app, "embedded.tmx");
+
+        let world = app.world_mut();
+        assert_eq!(world.query::<&TilePos>().iter(world).count(), 2);
+        let handle = world.get::<Handle<TiledMap>>(map).unwrap();
+        let tiled_map = world.resource::<Assets<TiledMap>>().get(handle).unwrap();
+        let images = world.resource::<Assets<Image>>();
+        // A `<data>` child, a `data:` URI and the image of an image layer, in document order.
+        let textures = [
+            tileset_image(tiled_map, 0),
+            tileset_image(tiled_map, 1),
+            &tiled_map.image_layer_textures[&2],
+        ];
+        for (i, texture) in textures.into_iter().enumerate() {
+            let path = texture.path().unwrap();
+            assert_eq!(path.path(), Path::new("embedded.tmx"));
+            assert_eq!(path.label(), Some(format!("embedded.tmx.{i}.png").as_str()));
+            assert_eq!(images.get(texture).unwrap().size(), UVec2::new(32, 16));
         }
     }
+
+    #[test]
+    fn hex_neighbors_match_the_staggered_rows_of_tiled() {
+        use bevy_ecs_tilemap::helpers::hex_grid::neighbors::HexDirection;
+        let mut app = spawn_test_app("assets");
+        let map = spawn_map(&mut app, "hexagonal.tmx");
+
+        let world = app.world();
+        let layer = world
+            .get::<TiledLayersStorage>(map)
+            .unwrap()
+            .layer_by_name("ground")
+            .unwrap();
+        let storage = world.get::<TileStorage>(layer).unwrap();
+        let map_type = world.get::<TilemapType>(layer).unwrap();
+        let source = |entity: Entity| {
+            let pos = world.get::<TilePos>(entity).unwrap();
+            // Rows of Tiled start at the top of the map.
+            (pos.x, storage.size.y - 1 - pos.y)
+        };
+        let neighbor = |x: u32, y: u32, direction| {
+            let pos = TilePos {
+                x,
+                y: storage.size.y - 1 - y,
+            };
+            crate::neighbors::tile_neighbor(storage, map_type, &pos, direction).map(source)
+        };
+        // Odd rows of the map are shifted right, by half a tile.
+        assert_eq!(neighbor(2, 2, HexDirection::One), Some((2, 1)));
+        assert_eq!(neighbor(2, 2, HexDirection::Two), Some((1, 1)));
+        assert_eq!(neighbor(2, 1, HexDirection::One), Some((3, 0)));
+        assert_eq!(neighbor(2, 1, HexDirection::Two), Some((2, 0)));
+        assert_eq!(neighbor(2, 1, HexDirection::Four), Some((2, 2)));
+        assert_eq!(neighbor(2, 2, HexDirection::Zero), Some((3, 2)));
+        assert_eq!(neighbor(5, 1, HexDirection::Zero), None);
+        assert_eq!(neighbor(2, 0, HexDirection::One), None);
+    }
+
+    #[test]
+    fn objects_keep_the_defaults_of_the_members_they_do_not_override() {
+        #[derive(Component, Reflect, Default, Debug, PartialEq)]
+        #[reflect(Component)]
+        struct Health(i32);
+
+        #[derive(Component, Reflect, Default, Debug, PartialEq)]
+        #[reflect(Component)]
+        struct Speed(f32);
+
+        let plugin = TiledBlueprintsPlugin {
+            loader: TiledLoader::default().with_project("classes.tiled-project"),
+            ..Default::default()
+        };
+        let mut app = crate::headless::spawn_test_app_with_plugin("assets/tests", plugin);
+        app.register_type::<Health>().register_type::<Speed>();
+        spawn_map(&mut app, "classes.tmx");
+
+        let world = app.world_mut();
+        let mut enemies: Vec<_> = world
+            .query::<(&Name, &Health, &Speed)>()
+            .iter(world)
+            .map(|(name, health, speed)| (name.as_str().to_string(), health.0, speed.0))
+            .collect();
+        enemies.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            enemies,
+            [
+                ("fresh".to_string(), 10, 1.5),
+                ("wounded".to_string(), 3, 1.5)
+            ]
+        );
+    }
 }
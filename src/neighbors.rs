@@ -0,0 +1,196 @@
+//! Neighbors of tiles, following the grid of the layer they were spawned in.
+//!
+//! Square, isometric and staggered layers have eight neighbors per tile, in the
+//! [`SquareDirection`]s, while hexagonal layers have six, in the [`HexDirection`]s of their
+//! [`HexCoordSystem`]. Directions follow the tile positions, so north is towards higher Y
+//! positions, which are up on the screen on orthogonal maps. This holds for maps loaded with
+//! [`YAxis::YDown`](crate::YAxis::YDown) in [`TiledMapSettings::y_axis`](crate::TiledMapSettings::y_axis)
+//! too, their tile layers are mirrored back upright.
+
+use bevy::prelude::*;
+use bevy_ecs_tilemap::{
+    helpers::{
+        hex_grid::{axial::AxialPos, neighbors::HexDirection},
+        square_grid::{
+            diamond::DiamondPos, neighbors::SquareDirection, staggered::StaggeredPos, SquarePos,
+        },
+    },
+    prelude::*,
+};
+
+/// Direction of a neighbor of a tile, see [`tile_neighbor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TileDirection {
+    /// Direction on square and isometric layers, diagonals included.
+    Square(SquareDirection),
+    /// Direction on hexagonal layers.
+    Hex(HexDirection),
+}
+
+impl From<SquareDirection> for TileDirection {
+    fn from(direction: SquareDirection) -> Self {
+        Self::Square(direction)
+    }
+}
+
+impl From<HexDirection> for TileDirection {
+    fn from(direction: HexDirection) -> Self {
+        Self::Hex(direction)
+    }
+}
+
+/// Position of the neighbor of a tile in a layer of the given size and type.
+///
+/// Returns `None` when the neighbor lies outside of the layer or when the direction does not
+/// match the grid of the layer, like a [`HexDirection`] on a square layer.
+pub fn neighbor_pos(
+    pos: &TilePos,
+    direction: impl Into<TileDirection>,
+    map_size: &TilemapSize,
+    map_type: &TilemapType,
+) -> Option<TilePos> {
+    match (map_type, direction.into()) {
+        (TilemapType::Square, TileDirection::Square(direction)) => SquarePos::from(pos)
+            .offset(&direction)
+            .as_tile_pos(map_size),
+        (TilemapType::Isometric(IsoCoordSystem::Diamond), TileDirection::Square(direction)) => {
+            DiamondPos::from(pos)
+                .offset(&direction)
+                .as_tile_pos(map_size)
+        }
+        (TilemapType::Isometric(IsoCoordSystem::Staggered), TileDirection::Square(direction)) => {
+            StaggeredPos::from(pos)
+                .offset(&direction)
+                .as_tile_pos(map_size)
+        }
+        (TilemapType::Hexagon(coord_sys), TileDirection::Hex(direction)) => {
+            AxialPos::from_tile_pos_given_coord_system(pos, *coord_sys)
+                .offset(direction)
+                .as_tile_pos_given_coord_system_and_map_size(*coord_sys, map_size)
+        }
+        _ => None,
+    }
+}
+
+/// Entity of the neighbor of a tile, from the [`TileStorage`] and [`TilemapType`] of its layer.
+///
+/// Returns `None` when there is no tile in that direction, see [`neighbor_pos`].
+pub fn tile_neighbor(
+    storage: &TileStorage,
+    map_type: &TilemapType,
+    pos: &TilePos,
+    direction: impl Into<TileDirection>,
+) -> Option<Entity> {
+    let neighbor = neighbor_pos(pos, direction, &storage.size, map_type)?;
+    storage.checked_get(&neighbor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: TilemapSize = TilemapSize { x: 3, y: 3 };
+
+    #[test]
+    fn square_neighbors_stop_at_the_edges_of_the_layer() {
+        let neighbor = |x, y, direction| {
+            neighbor_pos(&TilePos { x, y }, direction, &SIZE, &TilemapType::Square)
+        };
+        assert_eq!(
+            neighbor(0, 0, SquareDirection::North),
+            Some(TilePos { x: 0, y: 1 })
+        );
+        assert_eq!(
+            neighbor(0, 0, SquareDirection::NorthEast),
+            Some(TilePos { x: 1, y: 1 })
+        );
+        assert_eq!(neighbor(0, 0, SquareDirection::South), None);
+        assert_eq!(neighbor(0, 0, SquareDirection::West), None);
+        assert_eq!(neighbor(0, 0, SquareDirection::SouthWest), None);
+        assert_eq!(neighbor(2, 2, SquareDirection::North), None);
+        assert_eq!(neighbor(2, 2, SquareDirection::East), None);
+        assert_eq!(neighbor(2, 1, SquareDirection::NorthEast), None);
+        assert_eq!(
+            neighbor(2, 2, SquareDirection::SouthWest),
+            Some(TilePos { x: 1, y: 1 })
+        );
+    }
+
+    #[test]
+    fn directions_of_other_grids_have_no_neighbor() {
+        let pos = TilePos { x: 1, y: 1 };
+        let hex = TilemapType::Hexagon(HexCoordSystem::RowOdd);
+        assert_eq!(
+            neighbor_pos(&pos, HexDirection::Zero, &SIZE, &TilemapType::Square),
+            None
+        );
+        assert_eq!(
+            neighbor_pos(&pos, SquareDirection::North, &SIZE, &hex),
+            None
+        );
+    }
+
+    #[test]
+    fn hex_neighbors_follow_the_staggered_rows_and_columns() {
+        let neighbor = |coord_system, x, y, direction| {
+            neighbor_pos(
+                &TilePos { x, y },
+                direction,
+                &SIZE,
+                &TilemapType::Hexagon(coord_system),
+            )
+        };
+        // Odd rows are shifted right with `RowOdd`, even ones with `RowEven`.
+        let cases = [
+            (HexCoordSystem::RowOdd, 1, 0, Some(TilePos { x: 1, y: 1 })),
+            (HexCoordSystem::RowOdd, 1, 1, Some(TilePos { x: 2, y: 2 })),
+            (HexCoordSystem::RowEven, 1, 0, Some(TilePos { x: 2, y: 1 })),
+            (HexCoordSystem::RowEven, 1, 1, Some(TilePos { x: 1, y: 2 })),
+            (HexCoordSystem::RowOdd, 2, 1, None),
+            (HexCoordSystem::RowEven, 2, 0, None),
+        ];
+        for (coord_system, x, y, expected) in cases {
+            assert_eq!(
+                neighbor(coord_system, x, y, HexDirection::One),
+                expected,
+                "{coord_system:?} ({x}, {y})"
+            );
+        }
+        // Odd columns are shifted up with `ColumnOdd`, even ones with `ColumnEven`.
+        let cases = [
+            (
+                HexCoordSystem::ColumnOdd,
+                0,
+                1,
+                Some(TilePos { x: 1, y: 1 }),
+            ),
+            (
+                HexCoordSystem::ColumnOdd,
+                1,
+                1,
+                Some(TilePos { x: 2, y: 2 }),
+            ),
+            (
+                HexCoordSystem::ColumnEven,
+                0,
+                1,
+                Some(TilePos { x: 1, y: 2 }),
+            ),
+            (
+                HexCoordSystem::ColumnEven,
+                1,
+                1,
+                Some(TilePos { x: 2, y: 1 }),
+            ),
+            (HexCoordSystem::ColumnOdd, 1, 2, None),
+            (HexCoordSystem::ColumnEven, 0, 2, None),
+        ];
+        for (coord_system, x, y, expected) in cases {
+            assert_eq!(
+                neighbor(coord_system, x, y, HexDirection::Zero),
+                expected,
+                "{coord_system:?} ({x}, {y})"
+            );
+        }
+    }
+}
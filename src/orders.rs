@@ -0,0 +1,63 @@
+//! Render order of maps and draw orders of object layers, which the `tiled` crate does not read.
+//!
+//! They are read from the attributes of the TMX document before it is parsed. JSON maps are
+//! converted to TMX first, so their orders are read the same way.
+
+use bevy::utils::HashMap;
+
+use crate::embedded::attr;
+use crate::{ObjectDrawOrder, RenderOrder};
+
+/// Orders read from the attributes of a map.
+#[derive(Debug, Default)]
+pub(crate) struct MapOrders {
+    /// Render order of the map, `right-down` if it has none.
+    pub render_order: RenderOrder,
+    /// Draw orders of the object layers by layer id, layers without one use the default order.
+    pub object_draw_orders: HashMap<u32, ObjectDrawOrder>,
+}
+
+/// Reads the orders of a TMX document, the defaults of Tiled are kept for invalid documents.
+pub(crate) fn read_orders(document: &[u8]) -> MapOrders {
+    let mut orders = MapOrders::default();
+    let Ok(text) = std::str::from_utf8(document) else {
+        return orders;
+    };
+    // Collision shapes of tiles are object groups too, with ids of their own.
+    let mut in_tileset = false;
+    for (start, _) in text.match_indices('<') {
+        let Some(len) = text[start..].find('>') else {
+            break;
+        };
+        let tag = &text[start..start + len + 1];
+        if is_tag(tag, "<map") {
+            orders.render_order = match attr(tag, "renderorder") {
+                Some("right-up") => RenderOrder::RightUp,
+                Some("left-down") => RenderOrder::LeftDown,
+                Some("left-up") => RenderOrder::LeftUp,
+                _ => RenderOrder::RightDown,
+            };
+        } else if is_tag(tag, "<tileset") {
+            in_tileset = !tag.ends_with("/>");
+        } else if tag.starts_with("</tileset") {
+            in_tileset = false;
+        } else if !in_tileset && is_tag(tag, "<objectgroup") {
+            let id = attr(tag, "id").and_then(|id| id.parse().ok());
+            let draw_order = match attr(tag, "draworder") {
+                Some("index") => Some(ObjectDrawOrder::Index),
+                Some("topdown") => Some(ObjectDrawOrder::TopDown),
+                _ => None,
+            };
+            if let (Some(id), Some(draw_order)) = (id, draw_order) {
+                orders.object_draw_orders.insert(id, draw_order);
+            }
+        }
+    }
+    orders
+}
+
+/// Returns `true` if the tag is an element named like `name`, which includes its `<`.
+fn is_tag(tag: &str, name: &str) -> bool {
+    tag.strip_prefix(name)
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>'))
+}
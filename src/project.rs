@@ -0,0 +1,73 @@
+//! Default values of the members of custom classes, read from a Tiled project file
+//! (`.tiled-project`) set with [`TiledLoader::with_project`](crate::TiledLoader::with_project).
+//!
+//! Maps only store the members of a class that were changed on an object, the others keep the
+//! default of the class, which is only saved in the project. The `tiled` crate does not read
+//! project files, so the JSON is parsed here.
+//!
+//! Members of the `class` and `object` types are skipped, like class values in maps.
+
+use std::path::{Path, PathBuf};
+
+use bevy::utils::HashMap;
+use serde_json::{Map, Value};
+
+/// Reads the members of the classes of a project, by class name.
+///
+/// File members are relative to the project file, they are made relative to `map_dir` like the
+/// file properties of the map.
+pub(crate) fn class_defaults(
+    bytes: &[u8],
+    project_path: &Path,
+    map_dir: &Path,
+) -> Result<HashMap<String, tiled::Properties>, serde_json::Error> {
+    let project: Map<String, Value> = serde_json::from_slice(bytes)?;
+    // Paths relative to the map directory, climbing back to the assets folder first.
+    let project_dir: PathBuf = map_dir
+        .components()
+        .map(|_| Path::new(".."))
+        .collect::<PathBuf>()
+        .join(project_path.parent().unwrap_or(Path::new("")));
+
+    let mut classes = HashMap::default();
+    let property_types = project.get("propertyTypes").and_then(Value::as_array);
+    for property_type in property_types.into_iter().flatten() {
+        if property_type.get("type").and_then(Value::as_str) != Some("class") {
+            continue;
+        }
+        let Some(name) = property_type.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        let members = property_type.get("members").and_then(Value::as_array);
+        let properties = members
+            .into_iter()
+            .flatten()
+            .filter_map(|member| {
+                let name = member.get("name")?.as_str()?;
+                Some((name.to_string(), member_value(member, &project_dir)?))
+            })
+            .collect();
+        classes.insert(name.to_string(), properties);
+    }
+    Ok(classes)
+}
+
+fn member_value(member: &Value, project_dir: &Path) -> Option<tiled::PropertyValue> {
+    let value = member.get("value")?;
+    Some(match member.get("type")?.as_str()? {
+        "bool" => tiled::PropertyValue::BoolValue(value.as_bool()?),
+        "int" => tiled::PropertyValue::IntValue(value.as_i64()? as i32),
+        "float" => tiled::PropertyValue::FloatValue(value.as_f64()? as f32),
+        "string" => tiled::PropertyValue::StringValue(value.as_str()?.to_string()),
+        "color" => tiled::PropertyValue::ColorValue(value.as_str()?.parse().ok()?),
+        "file" => {
+            let file = value.as_str()?;
+            if file.is_empty() {
+                return None;
+            }
+            let path = crate::normalize_path(&project_dir.join(file));
+            tiled::PropertyValue::FileValue(path.to_string_lossy().into_owned())
+        }
+        _ => return None,
+    })
+}
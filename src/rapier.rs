@@ -0,0 +1,177 @@
+//! Colliders of [Rapier](https://rapier.rs), enabled with the `rapier` feature.
+//!
+//! Objects get a [`Collider`] matching their [`TiledObjectShape`] and a [`RigidBody::Fixed`],
+//! unless a `RigidBody` property inserted another one. Polygons are split into convex parts and
+//! ellipses which are not circles are approximated by polygons.
+//!
+//! Tiles with [`TileColliders`], generated with
+//! [`TiledMapSettings::generate_colliders`](crate::TiledMapSettings::generate_colliders), get a
+//! fixed rigid body with all of their shapes, as a compound collider or as child entities with
+//! [`TileColliderChildren`]. `RapierPhysicsPlugin` has to be added to the app.
+
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_rapier2d::{prelude::*, rapier::prelude::SharedShape};
+
+use crate::{
+    shapes::{TileColliderChildren, TileColliders, TiledObjectShape},
+    TiledSystems,
+};
+
+/// Number of points of the polygons approximating ellipses.
+const ELLIPSE_SEGMENTS: usize = 16;
+
+pub(crate) fn register(app: &mut App) {
+    app.add_systems(
+        Update,
+        (insert_object_colliders, insert_tile_colliders).after(TiledSystems::Process),
+    );
+}
+
+/// Convex parts of the collider of a shape, with their position and rotation relative to the
+/// position of the shape.
+///
+/// Compound colliders cannot be nested, so polygons are returned as their parts. Rectangles and
+/// ellipses are placed at their center, like in the debug display.
+fn shape_colliders(shape: &TiledObjectShape) -> Vec<(Vec2, f32, Collider)> {
+    match shape {
+        TiledObjectShape::Rect {
+            width,
+            height,
+            center,
+        } if *width > 0.0 && *height > 0.0 => {
+            vec![(*center, 0.0, Collider::cuboid(width / 2.0, height / 2.0))]
+        }
+        TiledObjectShape::Ellipse {
+            width,
+            height,
+            center,
+        } if *width > 0.0 && *height > 0.0 => {
+            let half_size = Vec2::new(width / 2.0, height / 2.0);
+            let collider = if width == height {
+                Collider::ball(half_size.x)
+            } else {
+                let points: Vec<Vec2> = (0..ELLIPSE_SEGMENTS)
+                    .map(|i| {
+                        let angle = i as f32 / ELLIPSE_SEGMENTS as f32 * std::f32::consts::TAU;
+                        Vec2::from_angle(angle) * half_size
+                    })
+                    .collect();
+                let Some(collider) = Collider::convex_hull(&points) else {
+                    return Vec::new();
+                };
+                collider
+            };
+            vec![(*center, 0.0, collider)]
+        }
+        TiledObjectShape::Polygon(points) if points.len() > 2 => {
+            let count = points.len() as u32;
+            let vertices: Vec<_> = points.iter().map(|point| (*point).into()).collect();
+            let indices: Vec<[u32; 2]> = (0..count).map(|i| [i, (i + 1) % count]).collect();
+            let decomposition = SharedShape::convex_decomposition(&vertices, &indices);
+            let Some(compound) = decomposition.as_compound() else {
+                return Vec::new();
+            };
+            compound
+                .shapes()
+                .iter()
+                .map(|(isometry, part)| {
+                    (
+                        isometry.translation.vector.into(),
+                        isometry.rotation.angle(),
+                        Collider::from(part.clone()),
+                    )
+                })
+                .collect()
+        }
+        TiledObjectShape::Polyline(points) if points.len() > 1 => {
+            vec![(Vec2::ZERO, 0.0, Collider::polyline(points.clone(), None))]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Collider of a shape placed at the position of the shape, a compound of its parts if needed.
+fn positioned_collider(shape: &TiledObjectShape) -> Option<Collider> {
+    let mut parts = shape_colliders(shape);
+    match parts.len() {
+        0 => None,
+        1 if parts[0].0 == Vec2::ZERO && parts[0].1 == 0.0 => Some(parts.remove(0).2),
+        _ => Some(Collider::compound(parts)),
+    }
+}
+
+fn insert_object_colliders(
+    mut commands: Commands,
+    objects: Query<(Entity, &TiledObjectShape, Has<RigidBody>), Added<TiledObjectShape>>,
+) {
+    for (entity, shape, has_rigid_body) in objects.iter() {
+        let Some(collider) = positioned_collider(shape) else {
+            continue;
+        };
+        let mut entity = commands.entity(entity);
+        entity.insert(collider);
+        if !has_rigid_body {
+            entity.insert(RigidBody::Fixed);
+        }
+    }
+}
+
+/// Tiles are not positioned with a [`Transform`] by `bevy_ecs_tilemap`, so it is inserted from
+/// their [`TilePos`].
+fn insert_tile_colliders(
+    mut commands: Commands,
+    tiles: Query<(Entity, &TilePos, &TilemapId, &TileColliders), Added<TileColliders>>,
+    tilemaps: Query<(&TilemapGridSize, &TilemapType)>,
+    collider_children: Query<(), With<TileColliderChildren>>,
+) {
+    for (entity, tile_pos, tilemap_id, tile_colliders) in tiles.iter() {
+        let Ok((grid_size, map_type)) = tilemaps.get(tilemap_id.0) else {
+            continue;
+        };
+        let center = tile_pos.center_in_world(grid_size, map_type);
+        let tile_transform =
+            TransformBundle::from_transform(Transform::from_translation(center.extend(0.0)));
+        if collider_children.contains(entity) {
+            let colliders: Vec<_> = tile_colliders
+                .0
+                .iter()
+                .filter_map(|(position, shape)| Some((*position, positioned_collider(shape)?)))
+                .collect();
+            if colliders.is_empty() {
+                continue;
+            }
+            commands
+                .entity(entity)
+                .insert((RigidBody::Fixed, tile_transform))
+                .with_children(|parent| {
+                    for (position, collider) in colliders {
+                        parent.spawn((
+                            collider,
+                            TransformBundle::from_transform(Transform::from_translation(
+                                position.extend(0.0),
+                            )),
+                        ));
+                    }
+                });
+            continue;
+        }
+        let shapes: Vec<_> = tile_colliders
+            .0
+            .iter()
+            .flat_map(|(position, shape)| {
+                shape_colliders(shape)
+                    .into_iter()
+                    .map(|(offset, rotation, collider)| (*position + offset, rotation, collider))
+            })
+            .collect();
+        if shapes.is_empty() {
+            continue;
+        }
+        commands.entity(entity).insert((
+            RigidBody::Fixed,
+            Collider::compound(shapes),
+            tile_transform,
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+
+/// Geometry of a Tiled object.
+///
+/// All coordinates are in Bevy space (Y pointing up) and relative to the position of the object,
+/// which in Tiled is the top-left corner for rectangles and ellipses, and the bottom-left corner
+/// for tile objects.
+#[derive(Debug, Reflect, Component, Default, Clone, PartialEq)]
+#[reflect(Component)]
+pub enum TiledObjectShape {
+    /// Rectangle centered on `center`, which spans to the right and down from the object position
+    /// unless the object is a tile. Text objects are represented by the rectangle of their text
+    /// box.
+    Rect {
+        width: f32,
+        height: f32,
+        center: Vec2,
+    },
+    /// Ellipse inscribed in the rectangle of the same size centered on `center`.
+    Ellipse {
+        width: f32,
+        height: f32,
+        center: Vec2,
+    },
+    /// Closed polygon.
+    Polygon(Vec<Vec2>),
+    /// Open polyline.
+    Polyline(Vec<Vec2>),
+    #[default]
+    Point,
+}
+
+/// Width and height in pixels of rectangle, ellipse, text and tile objects, for example the size
+/// of a trigger zone, without matching on their [`TiledObjectShape`].
+///
+/// Not inserted on objects without a size, like points, polygons and polylines.
+#[derive(Debug, Reflect, Component, Default, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+pub struct TiledObjectSize {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl TiledObjectSize {
+    /// Returns `None` if the shape has no width and height.
+    pub(crate) fn from_shape(shape: &tiled::ObjectShape) -> Option<Self> {
+        let (width, height) = match shape {
+            tiled::ObjectShape::Rect { width, height }
+            | tiled::ObjectShape::Ellipse { width, height }
+            | tiled::ObjectShape::Text { width, height, .. } => (*width, *height),
+            _ => return None,
+        };
+        (width != 0.0 || height != 0.0).then_some(Self { width, height })
+    }
+}
+
+/// Collision shapes of a tile, defined in the tileset.
+///
+/// Each shape is stored with its position relative to the center of the tile, in Bevy space.
+/// Flipping the tile does not affect the shapes.
+#[derive(Debug, Reflect, Component, Default, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct TileColliders(pub Vec<(Vec2, TiledObjectShape)>);
+
+/// Marks tiles whose [`TileColliders`] are spawned as child entities by the physics features,
+/// set with [`TiledMapSettings::colliders_as_children`](crate::TiledMapSettings::colliders_as_children).
+#[derive(Debug, Reflect, Component, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct TileColliderChildren;
+
+impl TileColliders {
+    /// Returns `None` if the tile has no collision shapes.
+    pub(crate) fn from_tile(tile: &tiled::Tile, flip_y: bool) -> Option<Self> {
+        let tileset = tile.tileset();
+        let half_size = Vec2::new(tileset.tile_width as f32, tileset.tile_height as f32) / 2.0;
+        let colliders: Vec<_> = tile
+            .collision
+            .as_ref()?
+            .object_data()
+            .iter()
+            .map(|object| {
+                let shape = TiledObjectShape::from(&object.shape);
+                let position = Vec2::new(object.x - half_size.x, half_size.y - object.y);
+                if flip_y {
+                    (position, shape)
+                } else {
+                    (Vec2::new(position.x, -position.y), shape.mirrored_y())
+                }
+            })
+            .collect();
+        (!colliders.is_empty()).then_some(Self(colliders))
+    }
+}
+
+impl TiledObjectShape {
+    /// Mirrors the points along the X axis, used for maps keeping the Y axis of Tiled.
+    /// Rectangles and ellipses then span up instead of down.
+    pub(crate) fn mirrored_y(self) -> Self {
+        let mirror = |p: Vec2| Vec2::new(p.x, -p.y);
+        match self {
+            TiledObjectShape::Rect {
+                width,
+                height,
+                center,
+            } => TiledObjectShape::Rect {
+                width,
+                height,
+                center: mirror(center),
+            },
+            TiledObjectShape::Ellipse {
+                width,
+                height,
+                center,
+            } => TiledObjectShape::Ellipse {
+                width,
+                height,
+                center: mirror(center),
+            },
+            TiledObjectShape::Polygon(points) => {
+                TiledObjectShape::Polygon(points.into_iter().map(mirror).collect())
+            }
+            TiledObjectShape::Polyline(points) => {
+                TiledObjectShape::Polyline(points.into_iter().map(mirror).collect())
+            }
+            TiledObjectShape::Point => TiledObjectShape::Point,
+        }
+    }
+
+    /// Moves the center of rectangles and ellipses, used for tile objects which are not placed
+    /// by their top-left corner.
+    pub(crate) fn centered_at(self, new_center: Vec2) -> Self {
+        match self {
+            TiledObjectShape::Rect { width, height, .. } => TiledObjectShape::Rect {
+                width,
+                height,
+                center: new_center,
+            },
+            TiledObjectShape::Ellipse { width, height, .. } => TiledObjectShape::Ellipse {
+                width,
+                height,
+                center: new_center,
+            },
+            shape => shape,
+        }
+    }
+}
+
+impl From<&tiled::ObjectShape> for TiledObjectShape {
+    fn from(shape: &tiled::ObjectShape) -> Self {
+        let to_points =
+            |points: &[(f32, f32)]| points.iter().map(|(x, y)| Vec2::new(*x, -*y)).collect();
+        match shape {
+            tiled::ObjectShape::Rect { width, height }
+            | tiled::ObjectShape::Text { width, height, .. } => TiledObjectShape::Rect {
+                width: *width,
+                height: *height,
+                center: Vec2::new(width / 2.0, -height / 2.0),
+            },
+            tiled::ObjectShape::Ellipse { width, height } => TiledObjectShape::Ellipse {
+                width: *width,
+                height: *height,
+                center: Vec2::new(width / 2.0, -height / 2.0),
+            },
+            tiled::ObjectShape::Polygon { points } => TiledObjectShape::Polygon(to_points(points)),
+            tiled::ObjectShape::Polyline { points } => {
+                TiledObjectShape::Polyline(to_points(points))
+            }
+            tiled::ObjectShape::Point(_, _) => TiledObjectShape::Point,
+        }
+    }
+}
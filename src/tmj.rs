@@ -0,0 +1,446 @@
+//! Support for maps exported in the Tiled JSON format (`.tmj`).
+//!
+//! The `tiled` crate only parses the XML based TMX format, so JSON maps are
+//! translated into an equivalent TMX document first and then handed to the
+//! regular TMX loader. That way the resulting [`crate::TiledMap`] is identical
+//! regardless of the source format.
+//!
+//! External tilesets (`.tsj`) and templates (`.tj`) are translated the same way
+//! when they are read, into TSX and TX documents.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+/// Extensions used by Tiled for JSON maps, tilesets and templates.
+pub(crate) const JSON_EXTENSIONS: &[&str] = &["tmj", "tsj", "tj", "json"];
+
+/// Returns `true` if the document should be parsed as JSON, either because of the
+/// file extension or because the content starts with `{`.
+pub(crate) fn is_json_document(path: &Path, bytes: &[u8]) -> bool {
+    let has_json_extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| JSON_EXTENSIONS.contains(&e));
+    has_json_extension
+        || bytes
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|b| *b == b'{')
+}
+
+/// Converts a Tiled JSON map, tileset or template into its XML document.
+pub(crate) fn to_tmx(bytes: &[u8]) -> Result<String, serde_json::Error> {
+    let document: Map<String, Value> = serde_json::from_slice(bytes)?;
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    match document.get("type").and_then(Value::as_str) {
+        Some("tileset") => write_tileset(&mut out, &document),
+        Some("template") => write_template(&mut out, &document),
+        _ => write_map(&mut out, &document),
+    }
+    Ok(out)
+}
+
+/// Writes a `<template>`, with the tileset of its tile object if it has one.
+fn write_template(out: &mut String, template: &Map<String, Value>) {
+    out.push_str("<template>\n");
+    if let Some(tileset) = template.get("tileset").and_then(Value::as_object) {
+        write_tileset(out, tileset);
+    }
+    if let Some(object) = template.get("object").and_then(Value::as_object) {
+        write_object(out, object);
+    }
+    out.push_str("</template>\n");
+}
+
+fn write_map(out: &mut String, map: &Map<String, Value>) {
+    out.push_str("<map");
+    write_attrs(
+        out,
+        map,
+        &[
+            "version",
+            "tiledversion",
+            "class",
+            "orientation",
+            "renderorder",
+            "width",
+            "height",
+            "tilewidth",
+            "tileheight",
+            "hexsidelength",
+            "staggeraxis",
+            "staggerindex",
+            "parallaxoriginx",
+            "parallaxoriginy",
+            "backgroundcolor",
+            "nextlayerid",
+            "nextobjectid",
+        ],
+    );
+    write_bool_attr(out, map, "infinite");
+    out.push_str(">\n");
+    write_properties(out, map);
+    for tileset in array(map, "tilesets") {
+        write_tileset(out, tileset);
+    }
+    for layer in array(map, "layers") {
+        write_layer(out, layer);
+    }
+    out.push_str("</map>\n");
+}
+
+fn write_tileset(out: &mut String, tileset: &Map<String, Value>) {
+    out.push_str("<tileset");
+    write_attrs(
+        out,
+        tileset,
+        &[
+            "firstgid",
+            "source",
+            "name",
+            "class",
+            "tilewidth",
+            "tileheight",
+            "spacing",
+            "margin",
+            "tilecount",
+            "columns",
+        ],
+    );
+    if tileset.contains_key("source") {
+        out.push_str("/>\n");
+        return;
+    }
+    out.push_str(">\n");
+    if let Some(offset) = tileset.get("tileoffset").and_then(Value::as_object) {
+        out.push_str("<tileoffset");
+        write_attrs(out, offset, &["x", "y"]);
+        out.push_str("/>\n");
+    }
+    write_image(out, tileset);
+    write_properties(out, tileset);
+    for tile in array(tileset, "tiles") {
+        out.push_str("<tile");
+        write_attrs(out, tile, &["id", "probability"]);
+        write_class_attr(out, tile);
+        out.push_str(">\n");
+        write_properties(out, tile);
+        write_image(out, tile);
+        if let Some(group) = tile.get("objectgroup").and_then(Value::as_object) {
+            write_layer(out, group);
+        }
+        let frames = array(tile, "animation");
+        if !frames.is_empty() {
+            out.push_str("<animation>\n");
+            for frame in frames {
+                out.push_str("<frame");
+                write_attrs(out, frame, &["tileid", "duration"]);
+                out.push_str("/>\n");
+            }
+            out.push_str("</animation>\n");
+        }
+        out.push_str("</tile>\n");
+    }
+    let wang_sets = array(tileset, "wangsets");
+    if !wang_sets.is_empty() {
+        out.push_str("<wangsets>\n");
+        for wang_set in wang_sets {
+            write_wang_set(out, wang_set);
+        }
+        out.push_str("</wangsets>\n");
+    }
+    out.push_str("</tileset>\n");
+}
+
+/// Writes a `<wangset>`, whose `type` is the type of the set rather than its class.
+fn write_wang_set(out: &mut String, wang_set: &Map<String, Value>) {
+    out.push_str("<wangset");
+    write_attrs(out, wang_set, &["name", "class", "type", "tile"]);
+    out.push_str(">\n");
+    write_properties(out, wang_set);
+    for color in array(wang_set, "colors") {
+        out.push_str("<wangcolor");
+        write_attrs(
+            out,
+            color,
+            &["name", "class", "color", "tile", "probability"],
+        );
+        out.push_str(">\n");
+        write_properties(out, color);
+        out.push_str("</wangcolor>\n");
+    }
+    for tile in array(wang_set, "wangtiles") {
+        out.push_str("<wangtile");
+        write_attrs(out, tile, &["tileid"]);
+        // JSON stores the Wang id as an array of colors.
+        if let Some(wang_id) = tile.get("wangid").and_then(Value::as_array) {
+            let colors: Vec<String> = wang_id.iter().map(value_to_string).collect();
+            let _ = write!(out, " wangid=\"{}\"", colors.join(","));
+        }
+        out.push_str("/>\n");
+    }
+    out.push_str("</wangset>\n");
+}
+
+/// Writes the `<image>` element described by the `image`, `imagewidth` and `imageheight` keys.
+fn write_image(out: &mut String, obj: &Map<String, Value>) {
+    let Some(source) = obj.get("image").and_then(Value::as_str) else {
+        return;
+    };
+    let _ = write!(out, "<image source=\"{}\"", escape(source));
+    // Image layers exported by older Tiled versions omit the size, which TMX requires.
+    for (key, attr) in [("imagewidth", "width"), ("imageheight", "height")] {
+        let size = obj.get(key).map(value_to_string);
+        let _ = write!(out, " {attr}=\"{}\"", size.as_deref().unwrap_or("0"));
+    }
+    if let Some(trans) = obj.get("transparentcolor").and_then(Value::as_str) {
+        let _ = write!(out, " trans=\"{}\"", escape(trans.trim_start_matches('#')));
+    }
+    out.push_str("/>\n");
+}
+
+const LAYER_ATTRS: &[&str] = &[
+    "id",
+    "name",
+    "class",
+    "opacity",
+    "offsetx",
+    "offsety",
+    "parallaxx",
+    "parallaxy",
+    "tintcolor",
+];
+
+fn write_layer(out: &mut String, layer: &Map<String, Value>) {
+    let (tag, extra): (&str, &[&str]) = match layer.get("type").and_then(Value::as_str) {
+        Some("tilelayer") => ("layer", &["width", "height"]),
+        Some("imagelayer") => ("imagelayer", &[]),
+        Some("group") => ("group", &[]),
+        _ => ("objectgroup", &["color", "draworder"]),
+    };
+    let _ = write!(out, "<{tag}");
+    write_attrs(out, layer, LAYER_ATTRS);
+    write_attrs(out, layer, extra);
+    write_bool_attr(out, layer, "visible");
+    out.push_str(">\n");
+    write_properties(out, layer);
+    match tag {
+        "layer" => write_tile_data(out, layer),
+        "imagelayer" => write_image(out, layer),
+        "group" => {
+            for child in array(layer, "layers") {
+                write_layer(out, child);
+            }
+        }
+        _ => {
+            for object in array(layer, "objects") {
+                write_object(out, object);
+            }
+        }
+    }
+    let _ = writeln!(out, "</{tag}>");
+}
+
+fn write_tile_data(out: &mut String, layer: &Map<String, Value>) {
+    let encoding = layer.get("encoding").and_then(Value::as_str);
+    let compression = layer
+        .get("compression")
+        .and_then(Value::as_str)
+        .filter(|c| !c.is_empty());
+    out.push_str("<data");
+    match encoding {
+        Some("base64") => {
+            out.push_str(" encoding=\"base64\"");
+            if let Some(compression) = compression {
+                let _ = write!(out, " compression=\"{}\"", escape(compression));
+            }
+        }
+        _ => out.push_str(" encoding=\"csv\""),
+    }
+    out.push_str(">\n");
+    let chunks = array(layer, "chunks");
+    if chunks.is_empty() {
+        write_gids(out, layer.get("data"));
+    } else {
+        for chunk in chunks {
+            out.push_str("<chunk");
+            write_attrs(out, chunk, &["x", "y", "width", "height"]);
+            out.push_str(">\n");
+            write_gids(out, chunk.get("data"));
+            out.push_str("</chunk>\n");
+        }
+    }
+    out.push_str("</data>\n");
+}
+
+/// Writes tile data either as CSV (when stored as a JSON array) or as the raw base64 string.
+fn write_gids(out: &mut String, data: Option<&Value>) {
+    match data {
+        Some(Value::Array(gids)) => {
+            let csv: Vec<String> = gids.iter().map(value_to_string).collect();
+            out.push_str(&csv.join(","));
+        }
+        Some(Value::String(s)) => out.push_str(&escape(s)),
+        _ => {}
+    }
+    out.push('\n');
+}
+
+fn write_object(out: &mut String, object: &Map<String, Value>) {
+    out.push_str("<object");
+    write_attrs(
+        out,
+        object,
+        &["id", "name", "gid", "x", "y", "rotation", "template"],
+    );
+    write_class_attr(out, object);
+    let is_point = object.get("point").and_then(Value::as_bool) == Some(true);
+    if !is_point {
+        write_attrs(out, object, &["width", "height"]);
+    }
+    write_bool_attr(out, object, "visible");
+    out.push_str(">\n");
+    write_properties(out, object);
+    if is_point {
+        out.push_str("<point/>\n");
+    } else if object.get("ellipse").and_then(Value::as_bool) == Some(true) {
+        out.push_str("<ellipse/>\n");
+    }
+    for kind in ["polygon", "polyline"] {
+        if let Some(points) = object.get(kind).and_then(Value::as_array) {
+            let points: Vec<String> = points
+                .iter()
+                .filter_map(Value::as_object)
+                .map(|p| {
+                    format!(
+                        "{},{}",
+                        p.get("x").map(value_to_string).unwrap_or_default(),
+                        p.get("y").map(value_to_string).unwrap_or_default()
+                    )
+                })
+                .collect();
+            let _ = writeln!(out, "<{kind} points=\"{}\"/>", points.join(" "));
+        }
+    }
+    if let Some(text) = object.get("text").and_then(Value::as_object) {
+        out.push_str("<text");
+        write_attrs(
+            out,
+            text,
+            &["fontfamily", "pixelsize", "color", "halign", "valign"],
+        );
+        for flag in [
+            "wrap",
+            "bold",
+            "italic",
+            "underline",
+            "strikeout",
+            "kerning",
+        ] {
+            write_bool_attr(out, text, flag);
+        }
+        let _ = writeln!(
+            out,
+            ">{}</text>",
+            escape(text.get("text").and_then(Value::as_str).unwrap_or_default())
+        );
+    }
+    out.push_str("</object>\n");
+}
+
+fn write_properties(out: &mut String, obj: &Map<String, Value>) {
+    let properties = array(obj, "properties");
+    if properties.is_empty() {
+        return;
+    }
+    out.push_str("<properties>\n");
+    for property in properties {
+        out.push_str("<property");
+        write_attrs(out, property, &["name", "type", "propertytype"]);
+        match property.get("value") {
+            Some(Value::Object(members)) => {
+                out.push('>');
+                write_class_members(out, members);
+                out.push_str("</property>\n");
+            }
+            Some(value) => {
+                let _ = writeln!(out, " value=\"{}\"/>", escape(&value_to_string(value)));
+            }
+            None => out.push_str("/>\n"),
+        }
+    }
+    out.push_str("</properties>\n");
+}
+
+/// JSON class values only store the member values, so the member types are inferred from them.
+fn write_class_members(out: &mut String, members: &Map<String, Value>) {
+    out.push_str("\n<properties>\n");
+    for (name, value) in members {
+        let _ = write!(out, "<property name=\"{}\"", escape(name));
+        match value {
+            Value::Object(nested) => {
+                out.push_str(" type=\"class\">");
+                write_class_members(out, nested);
+                out.push_str("</property>\n");
+                continue;
+            }
+            Value::Bool(_) => out.push_str(" type=\"bool\""),
+            Value::Number(n) if n.is_f64() => out.push_str(" type=\"float\""),
+            Value::Number(_) => out.push_str(" type=\"int\""),
+            _ => {}
+        }
+        let _ = writeln!(out, " value=\"{}\"/>", escape(&value_to_string(value)));
+    }
+    out.push_str("</properties>\n");
+}
+
+/// Older Tiled versions store the class of objects and tiles under `type`.
+fn write_class_attr(out: &mut String, obj: &Map<String, Value>) {
+    if let Some(class) = obj
+        .get("class")
+        .or_else(|| obj.get("type"))
+        .and_then(Value::as_str)
+    {
+        let _ = write!(out, " class=\"{}\"", escape(class));
+    }
+}
+
+fn write_attrs(out: &mut String, obj: &Map<String, Value>, keys: &[&str]) {
+    for key in keys {
+        if let Some(value) = obj.get(*key) {
+            let _ = write!(out, " {key}=\"{}\"", escape(&value_to_string(value)));
+        }
+    }
+}
+
+/// TMX stores booleans as `0`/`1`.
+fn write_bool_attr(out: &mut String, obj: &Map<String, Value>, key: &str) {
+    if let Some(value) = obj.get(key).and_then(Value::as_bool) {
+        let _ = write!(out, " {key}=\"{}\"", value as u8);
+    }
+}
+
+fn array<'a>(obj: &'a Map<String, Value>, key: &str) -> Vec<&'a Map<String, Value>> {
+    obj.get(key)
+        .and_then(Value::as_array)
+        .map(|a| a.iter().filter_map(Value::as_object).collect())
+        .unwrap_or_default()
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
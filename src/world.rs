@@ -0,0 +1,173 @@
+//! Tiled worlds, which place several maps next to each other.
+//!
+//! A `.world` file lists maps with their position in pixels, it is loaded as a [`TiledWorld`]
+//! and spawned with a [`TiledWorldBundle`]. Every map becomes a child of the world entity with a
+//! [`TiledMapBundle`], anchored at its top-left corner.
+
+use std::path::PathBuf;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AssetPath, AsyncReadExt},
+    log,
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    normalize_path, DespawnTiledMap, TiledAssetLoaderError, TiledMap, TiledMapAnchor,
+    TiledMapBundle,
+};
+
+pub(crate) fn register(app: &mut App) {
+    app.init_asset::<TiledWorld>()
+        .register_asset_loader(TiledWorldLoader)
+        .register_type::<TiledWorldMaps>()
+        .add_systems(
+            Update,
+            process_loaded_worlds
+                .before(crate::process_loaded_maps)
+                .in_set(crate::TiledSystems::Process),
+        );
+}
+
+/// A map of a [`TiledWorld`].
+#[derive(Debug, Clone)]
+pub struct TiledWorldMap {
+    /// Asset path of the map.
+    pub path: PathBuf,
+    /// Position of the top-left corner of the map, in Bevy space.
+    pub position: Vec2,
+    /// Handle of the map, set when the world was loaded with [`TiledWorldSettings::eager`].
+    pub handle: Option<Handle<TiledMap>>,
+}
+
+#[derive(TypePath, Asset, Debug)]
+pub struct TiledWorld {
+    pub maps: Vec<TiledWorldMap>,
+}
+
+/// Settings of the [`TiledWorldLoader`], passed with [`AssetServer::load_with_settings`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct TiledWorldSettings {
+    /// Loads the maps together with the world. Otherwise they are only loaded once the world
+    /// is spawned.
+    pub eager: bool,
+}
+
+#[derive(Deserialize)]
+struct WorldFile {
+    maps: Vec<WorldFileMap>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorldFileMap {
+    file_name: PathBuf,
+    x: f32,
+    y: f32,
+}
+
+#[derive(Default)]
+pub struct TiledWorldLoader;
+
+impl AssetLoader for TiledWorldLoader {
+    type Asset = TiledWorld;
+    type Settings = TiledWorldSettings;
+    type Error = TiledAssetLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        settings: &'a Self::Settings,
+        load_context: &'a mut bevy::asset::LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let world: WorldFile = serde_json::from_slice(&bytes)?;
+
+        let world_dir = load_context.path().parent().map(PathBuf::from);
+        let maps = world
+            .maps
+            .into_iter()
+            .map(|map| {
+                // Map paths are relative to the world file.
+                let path =
+                    normalize_path(&world_dir.clone().unwrap_or_default().join(map.file_name));
+                TiledWorldMap {
+                    handle: settings
+                        .eager
+                        .then(|| load_context.load(AssetPath::from(path.clone()))),
+                    path,
+                    position: Vec2::new(map.x, -map.y),
+                }
+            })
+            .collect();
+
+        log::info!("Loaded world: {}", load_context.path().display());
+        Ok(TiledWorld { maps })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        static EXTENSIONS: &[&str] = &["world"];
+        EXTENSIONS
+    }
+}
+
+/// Maps spawned for a world, in the order of [`TiledWorld::maps`].
+#[derive(Debug, Reflect, Component, Default, Clone)]
+#[reflect(Component)]
+pub struct TiledWorldMaps(pub Vec<Entity>);
+
+#[derive(Default, Bundle)]
+pub struct TiledWorldBundle {
+    pub tiled_world: Handle<TiledWorld>,
+    pub maps: TiledWorldMaps,
+    pub spatial: SpatialBundle,
+}
+
+/// Spawns the maps of loaded worlds, and respawns them when the world changes.
+pub fn process_loaded_worlds(
+    mut commands: Commands,
+    mut world_events: EventReader<AssetEvent<TiledWorld>>,
+    asset_server: Res<AssetServer>,
+    worlds: Res<Assets<TiledWorld>>,
+    mut world_query: Query<(Entity, &Handle<TiledWorld>, &mut TiledWorldMaps)>,
+    new_worlds: Query<&Handle<TiledWorld>, Added<Handle<TiledWorld>>>,
+) {
+    let mut changed_worlds: Vec<AssetId<TiledWorld>> = world_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    changed_worlds.extend(new_worlds.iter().map(|handle| handle.id()));
+
+    for (world_entity, world_handle, mut world_maps) in world_query.iter_mut() {
+        if !changed_worlds.contains(&world_handle.id()) {
+            continue;
+        }
+        let Some(tiled_world) = worlds.get(world_handle) else {
+            continue;
+        };
+        for map_entity in world_maps.0.drain(..) {
+            commands.despawn_tiled_map(map_entity);
+        }
+        for map in &tiled_world.maps {
+            let tiled_map = map
+                .handle
+                .clone()
+                .unwrap_or_else(|| asset_server.load(map.path.clone()));
+            let map_entity = commands
+                .spawn(TiledMapBundle {
+                    tiled_map,
+                    transform: Transform::from_translation(map.position.extend(0.0)),
+                    anchor: TiledMapAnchor::TopLeft,
+                    ..Default::default()
+                })
+                .set_parent(world_entity)
+                .id();
+            world_maps.0.push(map_entity);
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+use bevy::utils::Duration;
+use bevy_ecs_tilemap::tiles::TilePos;
+use bevy_tiled_blueprints::headless::{spawn_test_app, update_until_spawned};
+use bevy_tiled_blueprints::prelude::*;
+
+#[test]
+fn spawns_the_tiles_and_objects_of_a_map() {
+    let mut app = spawn_test_app("assets");
+    let map_handle: Handle<TiledMap> = app.world().resource::<AssetServer>().load("map.tmx");
+    let map = app.world_mut().spawn(TiledMapBundle::new(map_handle)).id();
+
+    assert!(update_until_spawned(&mut app, map, Duration::from_secs(10)));
+
+    let world = app.world_mut();
+    assert_eq!(
+        world.get::<TiledLayersStorage>(map).unwrap().storage.len(),
+        2
+    );
+    assert_eq!(world.query::<&TilePos>().iter(world).count(), 50 * 30);
+    assert_eq!(world.query::<&TiledObject>().iter(world).count(), 3);
+}